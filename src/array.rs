@@ -0,0 +1,580 @@
+//! Fixed-size and dynamically sized arrays of atomic pointers, e.g., for the
+//! buckets of hash tables or the child pointers of radix tree nodes.
+//!
+//! Both [`SlotArray`] and [`SlotSlice`] are generic over their element
+//! ([`Slot`]) type, which determines their ownership semantics and layout:
+//!
+//! - [`Atomic`] slots do not own their values, so the values must be
+//!   extracted manually (e.g., through [`take_all`][SlotArray::take_all])
+//!   before the array is dropped (see [`AtomicArray`] and [`AtomicSlice`]).
+//! - [`AtomicOwned`] slots own their values and de-allocate them when the
+//!   array is dropped (see [`AtomicOwnedArray`] and [`AtomicOwnedSlice`]).
+//! - [`CachePadded`] slots wrap either of the above and align every slot to
+//!   its own cache line in order to avoid false sharing between adjacent
+//!   slots.
+
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+use core::slice;
+use core::sync::atomic::Ordering;
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
+use crate::atomic::{Atomic, AtomicOwned};
+use crate::traits::{ProtectMany, Reclaim};
+use crate::{Owned, Protected, Unprotected};
+
+/// A fixed-size array of [`Atomic`] pointers.
+pub type AtomicArray<T, R, const N: usize, const LEN: usize> = SlotArray<Atomic<T, R, N>, LEN>;
+/// A fixed-size array of [`AtomicOwned`] pointers.
+pub type AtomicOwnedArray<T, R, const N: usize, const LEN: usize> =
+    SlotArray<AtomicOwned<T, R, N>, LEN>;
+/// A dynamically sized array of [`Atomic`] pointers.
+pub type AtomicSlice<T, R, const N: usize> = SlotSlice<Atomic<T, R, N>>;
+/// A dynamically sized array of [`AtomicOwned`] pointers.
+pub type AtomicOwnedSlice<T, R, const N: usize> = SlotSlice<AtomicOwned<T, R, N>>;
+
+// *************************************************************************************************
+// Slot (trait)
+// *************************************************************************************************
+
+/// A trait for the element types of [`SlotArray`] and [`SlotSlice`].
+pub trait Slot: Default {
+    /// The type of the owned values stored in the slot.
+    type Owned;
+    /// The type of unprotected snapshots of the slot.
+    type Unprotected;
+
+    /// Loads an unprotected snapshot of the slot's current value.
+    fn load_unprotected(&self, order: Ordering) -> Self::Unprotected;
+
+    /// Takes the slot's current value out of the slot, leaving it `null`.
+    ///
+    /// # Safety
+    ///
+    /// The same safety requirements as for [`Atomic::take`] apply.
+    unsafe fn take(&mut self) -> Option<Self::Owned>;
+}
+
+/********** impl Atomic ***************************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> Slot for Atomic<T, R, N> {
+    type Owned = Owned<T, R, N>;
+    type Unprotected = Unprotected<T, R, N>;
+
+    #[inline]
+    fn load_unprotected(&self, order: Ordering) -> Self::Unprotected {
+        Atomic::load_unprotected(self, order)
+    }
+
+    #[inline]
+    unsafe fn take(&mut self) -> Option<Self::Owned> {
+        Atomic::take(self)
+    }
+}
+
+/********** impl AtomicOwned **********************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> Slot for AtomicOwned<T, R, N> {
+    type Owned = Owned<T, R, N>;
+    type Unprotected = Unprotected<T, R, N>;
+
+    #[inline]
+    fn load_unprotected(&self, order: Ordering) -> Self::Unprotected {
+        AtomicOwned::load_unprotected(self, order)
+    }
+
+    #[inline]
+    unsafe fn take(&mut self) -> Option<Self::Owned> {
+        AtomicOwned::take(self)
+    }
+}
+
+/********** impl CachePadded **********************************************************************/
+
+impl<S: Slot> Slot for CachePadded<S> {
+    type Owned = S::Owned;
+    type Unprotected = S::Unprotected;
+
+    #[inline]
+    fn load_unprotected(&self, order: Ordering) -> Self::Unprotected {
+        self.inner.load_unprotected(order)
+    }
+
+    #[inline]
+    unsafe fn take(&mut self) -> Option<Self::Owned> {
+        self.inner.take()
+    }
+}
+
+// *************************************************************************************************
+// AsAtomic (trait)
+// *************************************************************************************************
+
+/// A trait for [`Slot`] types, which can be used like a (shared) [`Atomic`]
+/// pointer for protecting their values.
+pub trait AsAtomic<T, R, const N: usize> {
+    /// Returns a reference to the underlying [`Atomic`] pointer.
+    ///
+    /// # Safety
+    ///
+    /// The returned reference must only be used for loading (and protecting)
+    /// values, since storing values into it may violate the invariants of the
+    /// slot type (see [`AtomicOwned::as_atomic`]).
+    unsafe fn as_atomic(&self) -> &Atomic<T, R, N>;
+}
+
+/********** impl Atomic ***************************************************************************/
+
+impl<T, R, const N: usize> AsAtomic<T, R, N> for Atomic<T, R, N> {
+    #[inline]
+    unsafe fn as_atomic(&self) -> &Atomic<T, R, N> {
+        self
+    }
+}
+
+/********** impl AtomicOwned **********************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> AsAtomic<T, R, N> for AtomicOwned<T, R, N> {
+    #[inline]
+    unsafe fn as_atomic(&self) -> &Atomic<T, R, N> {
+        AtomicOwned::as_atomic(self)
+    }
+}
+
+/********** impl CachePadded **********************************************************************/
+
+impl<T, R, A: AsAtomic<T, R, N>, const N: usize> AsAtomic<T, R, N> for CachePadded<A> {
+    #[inline]
+    unsafe fn as_atomic(&self) -> &Atomic<T, R, N> {
+        self.inner.as_atomic()
+    }
+}
+
+// *************************************************************************************************
+// CachePadded
+// *************************************************************************************************
+
+/// A wrapper aligning its value to (at least) the size of a cache line.
+///
+/// The alignment of 128 bytes accounts for CPUs prefetching pairs of adjacent
+/// 64 byte cache lines.
+#[derive(Copy, Clone, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+#[repr(align(128))]
+pub struct CachePadded<T> {
+    inner: T,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T> CachePadded<T> {
+    /// Wraps the given `inner` value.
+    #[inline]
+    pub const fn new(inner: T) -> Self {
+        Self { inner }
+    }
+
+    /// Consumes `self` and returns the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T: fmt::Debug> fmt::Debug for CachePadded<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CachePadded").field(&self.inner).finish()
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+/********** impl DerefMut *************************************************************************/
+
+impl<T> DerefMut for CachePadded<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+// *************************************************************************************************
+// SlotArray
+// *************************************************************************************************
+
+/// A fixed-size array of `LEN` slots of type `S`.
+///
+/// Individual slots are accessed through the array's [`Deref`] implementation
+/// for slices.
+pub struct SlotArray<S, const LEN: usize> {
+    slots: [S; LEN],
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<S: Slot, const LEN: usize> SlotArray<S, LEN> {
+    /// Creates a new array with all slots set to `null`.
+    #[inline]
+    pub fn new() -> Self {
+        unsafe {
+            let mut slots: MaybeUninit<[S; LEN]> = MaybeUninit::uninit();
+            let ptr: *mut S = slots.as_mut_ptr().cast();
+            for idx in 0..LEN {
+                ptr.add(idx).write(S::default());
+            }
+
+            Self { slots: slots.assume_init() }
+        }
+    }
+
+    /// Returns an iterator over unprotected snapshots of all slots, each of
+    /// which is loaded with the given `order`.
+    #[inline]
+    pub fn iter(&self, order: Ordering) -> Iter<S> {
+        Iter { slots: self.slots.iter(), order }
+    }
+
+    /// Returns an iterator, which takes the values out of all non-null slots,
+    /// leaving them `null`.
+    ///
+    /// Slots which are not reached by the iterator are left untouched.
+    ///
+    /// # Safety
+    ///
+    /// The same safety requirements as for [`Slot::take`] apply to all slots.
+    #[inline]
+    pub unsafe fn take_all(&mut self) -> TakeAll<S> {
+        TakeAll { slots: self.slots.iter_mut() }
+    }
+
+    /// Consumes `self` and returns the array of all slots.
+    #[inline]
+    pub fn into_inner(self) -> [S; LEN] {
+        self.slots
+    }
+}
+
+impl<S, const LEN: usize> SlotArray<S, LEN> {
+    /// Loads and protects the values of all slots, each in the protection
+    /// slot of `guards` with the same index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `guards` has less than `LEN` protection slots.
+    #[inline]
+    pub fn protect_all<'g, T, G, const N: usize>(
+        &self,
+        guards: &'g mut G,
+        order: Ordering,
+    ) -> [Protected<'g, T, G::Reclaim, N>; LEN]
+    where
+        S: AsAtomic<T, G::Reclaim, N>,
+        G: ProtectMany<T>,
+    {
+        let mut protected = [Protected::null(); LEN];
+        protect_window(&self.slots, &mut protected, guards, order);
+        protected
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<S: Slot, const LEN: usize> Default for SlotArray<S, LEN> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<S: fmt::Debug, const LEN: usize> fmt::Debug for SlotArray<S, LEN> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SlotArray").field("slots", &&self.slots[..]).finish()
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<S, const LEN: usize> Deref for SlotArray<S, LEN> {
+    type Target = [S];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.slots
+    }
+}
+
+/********** impl DerefMut *************************************************************************/
+
+impl<S, const LEN: usize> DerefMut for SlotArray<S, LEN> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.slots
+    }
+}
+
+/********** impl From *****************************************************************************/
+
+impl<S, const LEN: usize> From<[S; LEN]> for SlotArray<S, LEN> {
+    #[inline]
+    fn from(slots: [S; LEN]) -> Self {
+        Self { slots }
+    }
+}
+
+// *************************************************************************************************
+// SlotSlice
+// *************************************************************************************************
+
+/// A heap allocated, dynamically sized array of slots of type `S`.
+///
+/// Individual slots are accessed through the slice's [`Deref`] implementation
+/// for slices.
+pub struct SlotSlice<S> {
+    slots: Box<[S]>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<S: Slot> SlotSlice<S> {
+    /// Creates a new slice of `len` slots, all of which are set to `null`.
+    #[inline]
+    pub fn with_len(len: usize) -> Self {
+        Self { slots: (0..len).map(|_| S::default()).collect() }
+    }
+
+    /// Returns an iterator over unprotected snapshots of all slots, each of
+    /// which is loaded with the given `order`.
+    #[inline]
+    pub fn iter(&self, order: Ordering) -> Iter<S> {
+        Iter { slots: self.slots.iter(), order }
+    }
+
+    /// Returns an iterator, which takes the values out of all non-null slots,
+    /// leaving them `null`.
+    ///
+    /// Slots which are not reached by the iterator are left untouched.
+    ///
+    /// # Safety
+    ///
+    /// The same safety requirements as for [`Slot::take`] apply to all slots.
+    #[inline]
+    pub unsafe fn take_all(&mut self) -> TakeAll<S> {
+        TakeAll { slots: self.slots.iter_mut() }
+    }
+
+    /// Consumes `self` and returns the boxed slice of all slots.
+    #[inline]
+    pub fn into_inner(self) -> Box<[S]> {
+        self.slots
+    }
+}
+
+impl<S> SlotSlice<S> {
+    /// Loads and protects the values of the `K` consecutive slots starting at
+    /// index `start`, each in the protection slot of `guards` with the same
+    /// relative index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range of slots is out of bounds or if `guards` has less
+    /// than `K` protection slots.
+    #[inline]
+    pub fn protect_range<'g, T, G, const N: usize, const K: usize>(
+        &self,
+        start: usize,
+        guards: &'g mut G,
+        order: Ordering,
+    ) -> [Protected<'g, T, G::Reclaim, N>; K]
+    where
+        S: AsAtomic<T, G::Reclaim, N>,
+        G: ProtectMany<T>,
+    {
+        let mut protected = [Protected::null(); K];
+        protect_window(&self.slots[start..start + K], &mut protected, guards, order);
+        protected
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<S: fmt::Debug> fmt::Debug for SlotSlice<S> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SlotSlice").field("slots", &&self.slots[..]).finish()
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<S> Deref for SlotSlice<S> {
+    type Target = [S];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.slots
+    }
+}
+
+/********** impl DerefMut *************************************************************************/
+
+impl<S> DerefMut for SlotSlice<S> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.slots
+    }
+}
+
+/********** impl From *****************************************************************************/
+
+impl<S> From<Box<[S]>> for SlotSlice<S> {
+    #[inline]
+    fn from(slots: Box<[S]>) -> Self {
+        Self { slots }
+    }
+}
+
+// *************************************************************************************************
+// Iter
+// *************************************************************************************************
+
+/// An iterator over unprotected snapshots of the slots of a [`SlotArray`] or
+/// [`SlotSlice`].
+#[derive(Debug)]
+pub struct Iter<'a, S> {
+    slots: slice::Iter<'a, S>,
+    order: Ordering,
+}
+
+/********** impl Iterator *************************************************************************/
+
+impl<S: Slot> Iterator for Iter<'_, S> {
+    type Item = S::Unprotected;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let order = self.order;
+        self.slots.next().map(|slot| slot.load_unprotected(order))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.slots.size_hint()
+    }
+}
+
+/********** impl ExactSizeIterator ****************************************************************/
+
+impl<S: Slot> ExactSizeIterator for Iter<'_, S> {}
+
+// *************************************************************************************************
+// TakeAll
+// *************************************************************************************************
+
+/// An iterator taking the values out of all non-null slots of a
+/// [`SlotArray`] or [`SlotSlice`].
+#[derive(Debug)]
+pub struct TakeAll<'a, S> {
+    slots: slice::IterMut<'a, S>,
+}
+
+/********** impl Iterator *************************************************************************/
+
+impl<S: Slot> Iterator for TakeAll<'_, S> {
+    type Item = S::Owned;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // safety: the caller of `take_all` guarantees that all slots can be taken from
+        self.slots.find_map(|slot| unsafe { slot.take() })
+    }
+}
+
+// *************************************************************************************************
+// helper function(s)
+// *************************************************************************************************
+
+#[inline]
+fn protect_window<'g, T, S, G, const N: usize>(
+    slots: &[S],
+    protected: &mut [Protected<'g, T, G::Reclaim, N>],
+    guards: &'g mut G,
+    order: Ordering,
+) where
+    S: AsAtomic<T, G::Reclaim, N>,
+    G: ProtectMany<T>,
+{
+    assert!(G::SLOTS >= protected.len(), "insufficient number of protection slots");
+    for (idx, (slot, protected)) in slots.iter().zip(protected.iter_mut()).enumerate() {
+        // safety: the atomic is only used for loading and protecting the slot's value
+        let ptr = guards.protect_slot(idx, unsafe { slot.as_atomic() }, order).into_marked_ptr();
+        // safety: every value remains protected in its own slot for as long as `guards` is borrowed
+        *protected = unsafe { Protected::from_marked_ptr(ptr) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::Ordering;
+
+    use super::{AtomicArray, AtomicOwnedArray, AtomicOwnedSlice, SlotArray};
+    use crate::guards::GuardArray;
+    use crate::leak::{Guard, Leaking};
+    use crate::Owned;
+
+    #[test]
+    fn take_all() {
+        let mut array: AtomicArray<i32, Leaking, 0, 4> = SlotArray::new();
+        array[1].store(Owned::new(1), Ordering::Relaxed);
+        array[3].store(Owned::new(3), Ordering::Relaxed);
+
+        let nulls = array.iter(Ordering::Relaxed).filter(|unprotected| unprotected.is_null());
+        assert_eq!(nulls.count(), 2);
+
+        let values: Vec<_> = unsafe { array.take_all() }.map(|owned| *owned).collect();
+        assert_eq!(values, [1, 3]);
+        assert!(array.iter(Ordering::Relaxed).all(|unprotected| unprotected.is_null()));
+    }
+
+    #[test]
+    fn owned_slice() {
+        let slice: AtomicOwnedSlice<i32, Leaking, 0> = AtomicOwnedSlice::with_len(8);
+        assert_eq!(slice.len(), 8);
+        slice[7].swap_owned(Owned::new(7), Ordering::Relaxed);
+        assert!(!slice.iter(Ordering::Relaxed).last().unwrap().is_null());
+    }
+
+    #[test]
+    fn protect_owned() {
+        let array: AtomicOwnedArray<i32, Leaking, 0, 2> = SlotArray::new();
+        array[1].swap_owned(Owned::new(1), Ordering::Release);
+
+        let mut guards = GuardArray::new([Guard; 2]);
+        let [a, b] = array.protect_all(&mut guards, Ordering::Acquire);
+        assert!(a.is_null());
+        assert_eq!(unsafe { b.as_ref() }, Some(&1));
+
+        let slice: AtomicOwnedSlice<i32, Leaking, 0> = AtomicOwnedSlice::with_len(4);
+        slice[2].swap_owned(Owned::new(2), Ordering::Release);
+        slice[3].swap_owned(Owned::new(3), Ordering::Release);
+
+        let [a, b] = slice.protect_range(2, &mut guards, Ordering::Acquire);
+        assert_eq!(unsafe { (a.as_ref(), b.as_ref()) }, (Some(&2), Some(&3)));
+    }
+}
@@ -1,5 +1,7 @@
 mod compare;
+mod owned;
 mod store;
+mod versioned;
 
 use core::fmt;
 use core::marker::PhantomData;
@@ -9,11 +11,14 @@ use core::sync::atomic::Ordering;
 
 use conquer_pointer::{AtomicMarkedPtr, MarkedNonNull, MarkedPtr, Null};
 
-use crate::traits::{Protect, Reclaim};
+use crate::ordering::{CasOrderings, LoadOrdering, Ordered, StoreOrdering, SwapOrdering};
+use crate::traits::{Protect, Reclaim, ReclaimThreadState};
 use crate::{Maybe, NotEqual, Owned, Protected, Unlinked, Unprotected};
 
 pub use self::compare::Comparable;
+pub use self::owned::AtomicOwned;
 pub use self::store::Storable;
+pub use self::versioned::{AtomicVersioned, Versioned};
 
 use self::compare::Unlink;
 
@@ -29,7 +34,7 @@ use self::compare::Unlink;
 /// goes out of scope.
 /// Use the (unsafe) [`take`][Atomic::take] method to extract an (optional)
 /// [`Owned`] value, which *does* correctly deallocate memory when it goes out
-/// of scope.
+/// of scope, or use an [`AtomicOwned`] instead.
 pub struct Atomic<T, R, const N: usize> {
     inner: AtomicMarkedPtr<T, N>,
     _marker: PhantomData<(T, R)>,
@@ -59,6 +64,9 @@ impl<T, R, const N: usize> Atomic<T, R, N> {
 /********** impl inherent *************************************************************************/
 
 impl<T, R: Reclaim<T>, const N: usize> Atomic<T, R, N> {
+    /// The mask for the tag bits of the underlying marked pointer.
+    const TAG_MASK: usize = (1 << N) - 1;
+
     /// Creates a new [`Atomic`] for the given `owned` record.
     #[inline]
     pub fn new(owned: Owned<T, R, N>) -> Self {
@@ -144,6 +152,23 @@ impl<T, R: Reclaim<T>, const N: usize> Atomic<T, R, N> {
         Unprotected { inner: self.load_raw(order), _marker: PhantomData }
     }
 
+    /// Loads a raw marked pointer from the [`Atomic`] with the memory
+    /// ordering represented by `O`.
+    #[inline]
+    pub fn load_raw_ordered<O: LoadOrdering>(&self, _: O) -> MarkedPtr<T, N> {
+        self.load_raw(O::ORDER)
+    }
+
+    /// Loads an [`Unprotected`] pointer from the [`Atomic`] with the memory
+    /// ordering represented by `O`.
+    ///
+    /// The returned pointer is explicitly **not** protected from reclamation,
+    /// meaning another thread could free the pointed to memory at any time.
+    #[inline]
+    pub fn load_unprotected_ordered<O: LoadOrdering>(&self, _: O) -> Unprotected<T, R, N> {
+        self.load_unprotected(O::ORDER)
+    }
+
     /// Stores either `null` or a valid pointer to an owned heap allocated value
     /// into the pointer.
     ///
@@ -153,6 +178,10 @@ impl<T, R: Reclaim<T>, const N: usize> Atomic<T, R, N> {
     ///
     /// `store` takes an [`Ordering`][ordering] argument, which
     /// describes the memory ordering of this operation.
+    /// Values stored with weaker than [`Release`][Ordering::Release] semantics
+    /// must be published by other means, since [`Ordered`] pointers loaded
+    /// with *acquire* semantics can be de-referenced safely (see
+    /// [`store_ordered`][Atomic::store_ordered]).
     ///
     /// # Panics
     ///
@@ -166,6 +195,21 @@ impl<T, R: Reclaim<T>, const N: usize> Atomic<T, R, N> {
         self.inner.store(new.into().into_marked_ptr(), order);
     }
 
+    /// Stores either `null` or a valid pointer to an owned heap allocated value
+    /// into the pointer with the memory ordering represented by `O`.
+    ///
+    /// Only [`StoreOrdering`]s with (at least)
+    /// [`Release`][crate::ordering::Release] semantics are accepted, so the
+    /// stored value is always *published* and [`Ordered`] pointers loaded
+    /// with an [`AcquireOrdering`][crate::ordering::AcquireOrdering] can be
+    /// de-referenced safely.
+    ///
+    /// See [`store`][Atomic::store] for further details.
+    #[inline]
+    pub fn store_ordered<O: StoreOrdering>(&self, new: impl Into<Storable<T, R, N>>, _: O) {
+        self.store(new, O::ORDER);
+    }
+
     /// Stores either `null` or a valid pointer to an owned heap allocated value
     /// into the pointer, returning the previous (now [`Unlinked`]) value
     /// wrapped in an [`Option`].
@@ -177,6 +221,8 @@ impl<T, R: Reclaim<T>, const N: usize> Atomic<T, R, N> {
     /// ordering of this operation. All ordering modes are possible. Note that using
     /// [`Acquire`][acquire] makes the store part of this operation [`Relaxed`][relaxed],
     /// and using [`Release`][release] makes the load part [`Relaxed`][relaxed].
+    /// As with [`store`][Atomic::store], values swapped in without (at least)
+    /// [`Release`][release] semantics must be published by other means.
     ///
     /// [ordering]: Ordering
     /// [relaxed]: Ordering::Relaxed
@@ -194,6 +240,92 @@ impl<T, R: Reclaim<T>, const N: usize> Atomic<T, R, N> {
         }
     }
 
+    /// Stores either `null` or a valid pointer to an owned heap allocated value
+    /// into the pointer with the memory ordering represented by `O`, returning
+    /// the previous (now [`Unlinked`]) value wrapped in a [`Maybe`].
+    ///
+    /// Like [`store_ordered`][Atomic::store_ordered], only [`SwapOrdering`]s
+    /// with (at least) [`Release`][crate::ordering::Release] semantics are
+    /// accepted.
+    ///
+    /// See [`swap`][Atomic::swap] for further details.
+    #[inline]
+    pub fn swap_ordered<O: SwapOrdering>(
+        &self,
+        new: impl Into<Storable<T, R, N>>,
+        _: O,
+    ) -> Maybe<Unlinked<T, R, N>> {
+        self.swap(new, O::ORDER)
+    }
+
+    /// Performs a bitwise *or* of the current tag value and `tag` and stores
+    /// the result into the [`Atomic`], leaving the pointer bits unchanged.
+    ///
+    /// Returns the previous value as an [`Unprotected`] pointer.
+    /// Any bits of `tag` exceeding the `N` available tag bits are ignored.
+    #[inline]
+    pub fn fetch_or_tag(&self, tag: usize, order: Ordering) -> Unprotected<T, R, N> {
+        let inner = self.inner.fetch_or(tag & Self::TAG_MASK, order);
+        Unprotected { inner, _marker: PhantomData }
+    }
+
+    /// Performs a bitwise *and* of the current tag value and `tag` and stores
+    /// the result into the [`Atomic`], leaving the pointer bits unchanged.
+    ///
+    /// Returns the previous value as an [`Unprotected`] pointer.
+    /// Any bits of `tag` exceeding the `N` available tag bits are ignored.
+    #[inline]
+    pub fn fetch_and_tag(&self, tag: usize, order: Ordering) -> Unprotected<T, R, N> {
+        let inner = self.inner.fetch_and(!Self::TAG_MASK | (tag & Self::TAG_MASK), order);
+        Unprotected { inner, _marker: PhantomData }
+    }
+
+    /// Performs a bitwise *xor* of the current tag value and `tag` and stores
+    /// the result into the [`Atomic`], leaving the pointer bits unchanged.
+    ///
+    /// Returns the previous value as an [`Unprotected`] pointer.
+    /// Any bits of `tag` exceeding the `N` available tag bits are ignored.
+    #[inline]
+    pub fn fetch_xor_tag(&self, tag: usize, order: Ordering) -> Unprotected<T, R, N> {
+        let inner = self.inner.fetch_xor(tag & Self::TAG_MASK, order);
+        Unprotected { inner, _marker: PhantomData }
+    }
+
+    /// Stores the `new` tag value into the [`Atomic`] if its current tag value
+    /// equals `current`, regardless of (and without changing) the pointer
+    /// bits.
+    ///
+    /// Returns the previous value as an [`Unprotected`] pointer in both cases.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the current tag value does not equal `current`, in which case
+    /// the actually present value is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `failure` is [`Release`][Ordering::Release],
+    /// [`AcqRel`][Ordering::AcqRel] or stronger than `success`.
+    #[inline]
+    pub fn compare_exchange_tag(
+        &self,
+        current: usize,
+        new: usize,
+        (success, failure): (Ordering, Ordering),
+    ) -> Result<Unprotected<T, R, N>, Unprotected<T, R, N>> {
+        let mut prev = self.inner.load(failure);
+        loop {
+            if prev.decompose_tag() != current & Self::TAG_MASK {
+                return Err(Unprotected { inner: prev, _marker: PhantomData });
+            }
+
+            match self.inner.compare_exchange_weak(prev, prev.set_tag(new), (success, failure)) {
+                Ok(_) => return Ok(Unprotected { inner: prev, _marker: PhantomData }),
+                Err(actual) => prev = actual,
+            }
+        }
+    }
+
     /// TODO: docs...
     #[inline]
     pub fn compare_exchange<C, S>(
@@ -246,6 +378,49 @@ impl<T, R: Reclaim<T>, const N: usize> Atomic<T, R, N> {
         }
     }
 
+    /// Stores `new` into the [`Atomic`] if its current value equals `current`,
+    /// with the pair of memory orderings represented by `O`.
+    ///
+    /// Like [`store_ordered`][Atomic::store_ordered], only [`CasOrderings`]
+    /// with (at least) [`Release`][crate::ordering::Release] semantics in case
+    /// of success are accepted.
+    ///
+    /// See [`compare_exchange`][Atomic::compare_exchange] for further details.
+    #[inline]
+    pub fn compare_exchange_ordered<O, C, S>(
+        &self,
+        current: C,
+        new: S,
+        _: O,
+    ) -> Result<C::Unlinked, CompareExchangeErr<S, T, R, N>>
+    where
+        O: CasOrderings,
+        C: Into<Comparable<T, R, N>> + Unlink + Copy,
+        S: Into<Storable<T, R, N>>,
+    {
+        self.compare_exchange(current, new, (O::SUCCESS, O::FAILURE))
+    }
+
+    /// Stores `new` into the [`Atomic`] if its current value equals `current`,
+    /// with the pair of memory orderings represented by `O`.
+    ///
+    /// Unlike [`compare_exchange_ordered`][Atomic::compare_exchange_ordered],
+    /// this function is allowed to spuriously fail.
+    #[inline]
+    pub fn compare_exchange_weak_ordered<O, C, S>(
+        &self,
+        current: C,
+        new: S,
+        _: O,
+    ) -> Result<C::Unlinked, CompareExchangeErr<S, T, R, N>>
+    where
+        O: CasOrderings,
+        C: Into<Comparable<T, R, N>> + Unlink + Copy,
+        S: Into<Storable<T, R, N>>,
+    {
+        self.compare_exchange_weak(current, new, (O::SUCCESS, O::FAILURE))
+    }
+
     #[inline]
     pub(crate) fn load_raw_if_equal(
         &self,
@@ -282,6 +457,84 @@ impl<T, R: Reclaim<T>, const N: usize> Atomic<T, R, N> {
         guard.protect(self, order)
     }
 
+    /// Loads a value from the pointer using `guard` to protect it from
+    /// reclamation, with the memory ordering represented by `O`.
+    ///
+    /// The returned pointer is wrapped in an [`Ordered`], which can be
+    /// de-referenced if `O` is an [`AcquireOrdering`][crate::ordering::AcquireOrdering]
+    /// and the value was published with (at least) [`Release`][Ordering::Release]
+    /// semantics.
+    #[inline]
+    pub fn load_ordered<'g, O: LoadOrdering>(
+        &self,
+        guard: &'g mut impl Protect<T, Reclaim = R>,
+        order: O,
+    ) -> Ordered<Protected<'g, T, R, N>, O> {
+        guard.protect_ordered(self, order)
+    }
+
+    /// Repeatedly loads the current value using `guard` and attempts to
+    /// replace it with the value returned by `func` until either the
+    /// *compare-and-swap* succeeds or `func` returns [`None`].
+    ///
+    /// `func` is called with the currently loaded value and, if a previous
+    /// attempt failed, the value it had returned in that attempt, so that e.g.
+    /// [`Owned`] values can be re-used instead of re-allocated.
+    /// On success, the replaced (non-null) value is retired through
+    /// `thread_state` exactly once and returned as a [`Protected`] pointer,
+    /// which remains protected by `guard` until it is used again, i.e., the
+    /// retired record can not be reclaimed before then and its value can
+    /// still be read (or taken) through the returned pointer.
+    ///
+    /// `load` is the memory ordering for (re-)loading the current value, while
+    /// `cas` are the memory orderings for the *compare-and-swap* operation.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if `func` returns [`None`], in which case the currently loaded
+    /// value is returned.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure that retiring the replaced value is sound,
+    /// i.e., that no other thread is able to load a new reference to it after
+    /// it has been replaced (see [`Unlinked`]).
+    /// Also, `thread_state` must be derived from the same reclaimer instance as
+    /// the guard and all values stored in the [`Atomic`].
+    #[inline]
+    pub unsafe fn update<'g, S>(
+        &self,
+        guard: &'g mut impl Protect<T, Reclaim = R>,
+        thread_state: &impl ReclaimThreadState<T, Reclaim = R>,
+        load: Ordering,
+        cas: (Ordering, Ordering),
+        mut func: impl for<'a> FnMut(Protected<'a, T, R, N>, Option<S>) -> Option<S>,
+    ) -> Result<Protected<'g, T, R, N>, Protected<'g, T, R, N>>
+    where
+        S: Into<Storable<T, R, N>>,
+    {
+        let mut input = None;
+        loop {
+            let current = guard.protect(self, load).into_marked_ptr();
+            let protected = Protected::from_marked_ptr(current);
+            let new = match func(protected, input.take()) {
+                Some(new) => new,
+                None => return Err(Protected::from_marked_ptr(current)),
+            };
+
+            match self.compare_exchange_weak(protected, new, cas) {
+                Ok(unlinked) => {
+                    if let Maybe::Some(unlinked) = unlinked {
+                        thread_state.retire_record(unlinked.into_retired());
+                    }
+
+                    return Ok(Protected::from_marked_ptr(current));
+                }
+                Err(err) => input = Some(err.input),
+            }
+        }
+    }
+
     /// TODO: docs...
     #[inline]
     pub fn load_if_equal<'g>(
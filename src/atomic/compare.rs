@@ -3,6 +3,7 @@ use core::marker::PhantomData;
 
 use conquer_pointer::{MarkedNonNull, MarkedPtr, Null};
 
+use crate::ordering::Ordered;
 use crate::{Maybe, Protected, Shared, Unlinked, Unprotected};
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -58,6 +59,15 @@ impl<T, R, const N: usize> fmt::Debug for Comparable<T, R, N> {
     impl_fmt_debug!(Comparable);
 }
 
+/********** impl From (Ordered) *******************************************************************/
+
+impl<P: Into<Self>, O, T, R, const N: usize> From<Ordered<P, O>> for Comparable<T, R, N> {
+    #[inline]
+    fn from(ordered: Ordered<P, O>) -> Self {
+        ordered.inner.into()
+    }
+}
+
 /********** impl From (Protected) *****************************************************************/
 
 impl<T, R, const N: usize> From<Protected<'_, T, R, N>> for Comparable<T, R, N> {
@@ -141,3 +151,14 @@ impl<T, R, const N: usize> Unlink for Unprotected<T, R, N> {
         }
     }
 }
+
+/********** impl Ordered **************************************************************************/
+
+impl<P: Unlink, O> Unlink for Ordered<P, O> {
+    type Unlinked = P::Unlinked;
+
+    #[inline]
+    unsafe fn into_unlinked(self) -> Self::Unlinked {
+        self.inner.into_unlinked()
+    }
+}
@@ -0,0 +1,214 @@
+use core::fmt;
+use core::sync::atomic::Ordering;
+
+use conquer_pointer::MarkedPtr;
+
+use crate::atomic::compare::Unlink;
+use crate::atomic::{Atomic, Comparable, CompareExchangeErr, Storable};
+use crate::traits::{Protect, Reclaim};
+use crate::{Maybe, NotEqual, Owned, Protected, Unlinked, Unprotected};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// AtomicOwned
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An atomic marked pointer type, which is the unique owner of the value it
+/// points to, if any.
+///
+/// Unlike [`Atomic`], only [`Owned`] values (or `null`) can ever be stored
+/// into an `AtomicOwned`, so the *uniqueness* invariant is maintained by
+/// construction.
+/// Consequently, replaced values are returned as [`Unlinked`] and can be
+/// retired, and the current value is de-allocated when the `AtomicOwned` is
+/// dropped.
+pub struct AtomicOwned<T, R: Reclaim<T>, const N: usize> {
+    inner: Atomic<T, R, N>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> AtomicOwned<T, R, N> {
+    /// Creates a new `null` pointer.
+    #[inline]
+    pub fn null() -> Self {
+        Self { inner: Atomic::null() }
+    }
+
+    /// Creates a new [`AtomicOwned`] for the given `owned` record.
+    #[inline]
+    pub fn new(owned: Owned<T, R, N>) -> Self {
+        Self { inner: Atomic::new(owned) }
+    }
+
+    /// Returns a reference to the underlying [`Atomic`].
+    ///
+    /// # Safety
+    ///
+    /// The returned reference must not be used to store any values other than
+    /// [`Owned`] ones or `null` into the pointer, since this would violate the
+    /// *uniqueness* invariant.
+    #[inline]
+    pub unsafe fn as_atomic(&self) -> &Atomic<T, R, N> {
+        &self.inner
+    }
+
+    /// Takes the current value out of the pointer, leaving it `null`.
+    #[inline]
+    pub fn take(&mut self) -> Option<Owned<T, R, N>> {
+        // safety: `&mut self` guarantees there are no concurrent accesses and the pointer is the
+        // unique owner of its value
+        unsafe { self.inner.take() }
+    }
+
+    /// Consumes the [`AtomicOwned`] and returns its current value.
+    #[inline]
+    pub fn into_owned(mut self) -> Option<Owned<T, R, N>> {
+        self.take()
+    }
+
+    /// Returns a mutable reference to the current value, if it is non-null.
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        // safety: `&mut self` guarantees there are no concurrent accesses and the pointer is the
+        // unique owner of its value
+        unsafe { self.inner.load_raw(Ordering::Relaxed).as_mut() }
+    }
+
+    /// Loads a raw marked pointer from the [`AtomicOwned`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is [`Release`][Ordering::Release] or
+    /// [`AcqRel`][Ordering::AcqRel].
+    #[inline]
+    pub fn load_raw(&self, order: Ordering) -> MarkedPtr<T, N> {
+        self.inner.load_raw(order)
+    }
+
+    /// Loads an [`Unprotected`] pointer from the [`AtomicOwned`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is [`Release`][Ordering::Release] or
+    /// [`AcqRel`][Ordering::AcqRel].
+    #[inline]
+    pub fn load_unprotected(&self, order: Ordering) -> Unprotected<T, R, N> {
+        self.inner.load_unprotected(order)
+    }
+
+    /// Loads a value from the pointer using `guard` to protect it from
+    /// reclamation.
+    ///
+    /// # Panics
+    ///
+    /// *May* panic if `order` is [`Release`][Ordering::Release] or
+    /// [`AcqRel`][Ordering::AcqRel].
+    #[inline]
+    pub fn load<'g>(
+        &self,
+        guard: &'g mut impl Protect<T, Reclaim = R>,
+        order: Ordering,
+    ) -> Protected<'g, T, R, N> {
+        guard.protect(&self.inner, order)
+    }
+
+    /// Loads a value from the pointer using `guard` to protect it from
+    /// reclamation, if it is equal to `expected`.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the loaded value is not equal to `expected`.
+    #[inline]
+    pub fn load_if_equal<'g>(
+        &self,
+        expected: MarkedPtr<T, N>,
+        guard: &'g mut impl Protect<T, Reclaim = R>,
+        order: Ordering,
+    ) -> Result<Protected<'g, T, R, N>, NotEqual> {
+        guard.protect_if_equal(&self.inner, expected, order)
+    }
+
+    /// Stores the `new` value into the pointer and returns the previous
+    /// (now [`Unlinked`]) value, if it was non-null.
+    #[inline]
+    pub fn swap_owned(&self, new: Owned<T, R, N>, order: Ordering) -> Maybe<Unlinked<T, R, N>> {
+        self.inner.swap(new, order)
+    }
+
+    /// Stores either the `new` value or `null` into the pointer and returns
+    /// the previous (now [`Unlinked`]) value, if it was non-null.
+    #[inline]
+    pub fn replace(
+        &self,
+        new: Option<Owned<T, R, N>>,
+        order: Ordering,
+    ) -> Maybe<Unlinked<T, R, N>> {
+        self.inner.swap(new.map(Storable::from).unwrap_or_else(Storable::null), order)
+    }
+
+    /// Stores the `new` value into the pointer, if the current value is equal
+    /// to `current`, and returns the previous (now unlinked) value.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the current value is not equal to `current`, in which case
+    /// the actually loaded value and `new` are returned.
+    #[inline]
+    pub fn compare_exchange_owned<C>(
+        &self,
+        current: C,
+        new: Owned<T, R, N>,
+        orders: (Ordering, Ordering),
+    ) -> Result<C::Unlinked, CompareExchangeErr<Owned<T, R, N>, T, R, N>>
+    where
+        C: Into<Comparable<T, R, N>> + Unlink + Copy,
+    {
+        self.inner.compare_exchange(current, new, orders)
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> Default for AtomicOwned<T, R, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::null()
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> fmt::Debug for AtomicOwned<T, R, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (ptr, tag) = self.inner.load_raw(Ordering::SeqCst).decompose();
+        f.debug_struct("AtomicOwned").field("ptr", &ptr).field("tag", &tag).finish()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> Drop for AtomicOwned<T, R, N> {
+    #[inline]
+    fn drop(&mut self) {
+        self.take();
+    }
+}
+
+/********** impl From *****************************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> From<Owned<T, R, N>> for AtomicOwned<T, R, N> {
+    #[inline]
+    fn from(owned: Owned<T, R, N>) -> Self {
+        Self::new(owned)
+    }
+}
+
+/********** impl Pointer **************************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> fmt::Pointer for AtomicOwned<T, R, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&self.inner, f)
+    }
+}
@@ -0,0 +1,549 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ptr;
+use core::sync::atomic::{self, AtomicUsize, Ordering};
+
+use conquer_pointer::{MarkedNonNull, MarkedPtr, Null};
+
+use crate::atomic::{Atomic, Storable};
+use crate::traits::{Protect, Reclaim};
+use crate::{Maybe, NotEqual, Owned, Protected, Unlinked};
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// AtomicVersioned
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An atomic marked pointer paired with a (word-sized) version counter, which
+/// is incremented by every successful *compare-and-swap* operation.
+///
+/// Unlike tag bits, the version counter is wide enough to reliably detect ABA
+/// problems, e.g., for data structures that recycle their nodes instead of
+/// (or before) reclaiming them.
+///
+/// Both words are updated at once using double-width *compare-and-swap*
+/// instructions (`cmpxchg16b`), if the crate's `nightly` feature is enabled
+/// and the target supports them, and through a sequence lock otherwise.
+/// Loading only the pointer (e.g., through a [`Protect`] guard) never blocks.
+#[repr(C, align(16))]
+pub struct AtomicVersioned<T, R, const N: usize> {
+    ptr: Atomic<T, R, N>,
+    version: AtomicUsize,
+    lock: imp::Lock,
+}
+
+/********** impl inherent (const) *****************************************************************/
+
+impl<T, R, const N: usize> AtomicVersioned<T, R, N> {
+    /// Creates a new `null` pointer with version 0.
+    #[inline]
+    pub const fn null() -> Self {
+        Self { ptr: Atomic::null(), version: AtomicUsize::new(0), lock: imp::Lock::new() }
+    }
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> AtomicVersioned<T, R, N> {
+    /// Creates a new [`AtomicVersioned`] for the given `owned` record with
+    /// version 0.
+    #[inline]
+    pub fn new(owned: Owned<T, R, N>) -> Self {
+        Self { ptr: Atomic::new(owned), version: AtomicUsize::new(0), lock: imp::Lock::new() }
+    }
+
+    /// Returns a reference to the underlying pointer (without the version).
+    ///
+    /// # Safety
+    ///
+    /// The returned reference must not be used to store any values into the
+    /// pointer, since this would circumvent the version counter.
+    #[inline]
+    pub unsafe fn as_atomic(&self) -> &Atomic<T, R, N> {
+        &self.ptr
+    }
+
+    /// Takes the value out of the pointer as an [`Owned`], leaving a `null`
+    /// pointer in its place, without incrementing the version.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to ensure that the taken value is not concurrently
+    /// accessed through any protected reference and is not retired otherwise.
+    #[inline]
+    pub unsafe fn take(&mut self) -> Option<Owned<T, R, N>> {
+        self.ptr.take()
+    }
+
+    /// Loads a consistent snapshot of the raw marked pointer and its version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is [`Release`][Ordering::Release] or
+    /// [`AcqRel`][Ordering::AcqRel].
+    #[inline]
+    pub fn load_raw(&self, order: Ordering) -> Versioned<T, N> {
+        imp::load(self, order)
+    }
+
+    /// Loads the current value using `guard` to protect it from reclamation
+    /// and returns it together with its version.
+    ///
+    /// # Panics
+    ///
+    /// *May* panic if `order` is [`Release`][Ordering::Release] or
+    /// [`AcqRel`][Ordering::AcqRel].
+    #[inline]
+    pub fn load<'g>(
+        &self,
+        guard: &'g mut impl Protect<T, Reclaim = R>,
+        order: Ordering,
+    ) -> (Protected<'g, T, R, N>, usize) {
+        loop {
+            let expected = self.load_raw(Ordering::Relaxed);
+            // SAFETY: the guard is only re-borrowed for each attempt, which is
+            // sound since only the protected value of the last one is returned
+            let guard = unsafe { &mut *(guard as *mut _) };
+            if let Ok(protected) = self.load_if_equal(expected, guard, order) {
+                return (protected, expected.version);
+            }
+        }
+    }
+
+    /// Loads and protects the current value using `guard` if both its pointer
+    /// and its version equal the `expected` snapshot.
+    ///
+    /// This is equivalent to calling
+    /// [`protect_if_equal_versioned`][Protect::protect_if_equal_versioned] on
+    /// `guard`.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if either the pointer or the version have changed.
+    #[inline]
+    pub fn load_if_equal<'g, G: Protect<T, Reclaim = R>>(
+        &self,
+        expected: Versioned<T, N>,
+        guard: &'g mut G,
+        order: Ordering,
+    ) -> Result<Protected<'g, T, R, N>, NotEqual> {
+        loop {
+            let seq = imp::read_begin(self);
+            // SAFETY: the guard is only re-borrowed for each attempt, which is
+            // sound since only the protected value of the last one is returned
+            let guard = unsafe { &mut *(guard as *mut G) };
+            let protected = guard.protect_if_equal(&self.ptr, expected.ptr, order)?;
+            // the version must not be read before the pointer, otherwise an
+            // A -> B -> A change in between the two reads would go undetected
+            atomic::fence(Ordering::Acquire);
+            let version = self.version.load(Ordering::Relaxed);
+            // the version is incremented (wrapping) together with every change
+            // of the pointer, so if both are read consistently (i.e. without
+            // any concurrent write in between) and the version still equals
+            // the expected one, the pointer has not changed since the snapshot
+            if imp::read_validate(self, seq) {
+                return match version {
+                    version if version == expected.version => Ok(protected),
+                    _ => Err(NotEqual),
+                };
+            }
+        }
+    }
+
+    /// Stores `new` into the pointer and increments the version, if both the
+    /// current pointer and version equal the `current` snapshot.
+    ///
+    /// On success, the previous value is returned as an [`Unlinked`] record
+    /// (if it was non-null), which can be retired like the result of
+    /// [`Atomic::compare_exchange`].
+    /// Like for the latter, the caller has to ensure the previous value is
+    /// actually no longer reachable before retiring it, e.g., it must not be
+    /// stored again as `new`.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if either the pointer or the version have changed, in which case
+    /// the actually present snapshot and `new` are returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `failure` is [`Release`][Ordering::Release],
+    /// [`AcqRel`][Ordering::AcqRel] or stronger than `success`.
+    #[inline]
+    pub fn compare_exchange<S: Into<Storable<T, R, N>>>(
+        &self,
+        current: Versioned<T, N>,
+        new: S,
+        (success, failure): (Ordering, Ordering),
+    ) -> Result<Maybe<Unlinked<T, R, N>>, (Versioned<T, N>, S)> {
+        let new = ManuallyDrop::new(new);
+        let store = unsafe { ptr::read(&*new) }.into().into_marked_ptr();
+        match imp::compare_exchange(self, current, store, (success, failure)) {
+            Ok(prev) => match MarkedNonNull::new(prev.ptr) {
+                Ok(inner) => Ok(Maybe::Some(Unlinked { inner, _marker: PhantomData })),
+                Err(Null(tag)) => Ok(Maybe::Null(tag)),
+            },
+            Err(actual) => Err((actual, ManuallyDrop::into_inner(new))),
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> Default for AtomicVersioned<T, R, N> {
+    default_null!();
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T, R: Reclaim<T>, const N: usize> fmt::Debug for AtomicVersioned<T, R, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Versioned { ptr, version } = self.load_raw(Ordering::SeqCst);
+        let (ptr, tag) = ptr.decompose();
+        f.debug_struct("AtomicVersioned")
+            .field("ptr", &ptr)
+            .field("tag", &tag)
+            .field("version", &version)
+            .finish()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// Versioned
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A snapshot of the raw marked pointer and the version of an
+/// [`AtomicVersioned`].
+pub struct Versioned<T, const N: usize> {
+    /// The raw marked pointer.
+    pub ptr: MarkedPtr<T, N>,
+    /// The version counter.
+    pub version: usize,
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl<T, const N: usize> Clone for Versioned<T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { ptr: self.ptr, version: self.version }
+    }
+}
+
+/********** impl Copy *****************************************************************************/
+
+impl<T, const N: usize> Copy for Versioned<T, N> {}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T, const N: usize> fmt::Debug for Versioned<T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (ptr, tag) = self.ptr.decompose();
+        f.debug_struct("Versioned")
+            .field("ptr", &ptr)
+            .field("tag", &tag)
+            .field("version", &self.version)
+            .finish()
+    }
+}
+
+/********** impl PartialEq ************************************************************************/
+
+impl<T, const N: usize> PartialEq for Versioned<T, N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr && self.version == other.version
+    }
+}
+
+/********** impl Eq *******************************************************************************/
+
+impl<T, const N: usize> Eq for Versioned<T, N> {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// imp (cmpxchg16b)
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(feature = "nightly", target_arch = "x86_64", target_feature = "cmpxchg16b"))]
+mod imp {
+    use core::arch::x86_64::cmpxchg16b;
+    use core::sync::atomic::Ordering;
+
+    use conquer_pointer::MarkedPtr;
+
+    use super::{AtomicVersioned, Versioned};
+
+    /// No lock is required, when double-width CAS instructions are available.
+    #[derive(Default)]
+    pub(super) struct Lock;
+
+    impl Lock {
+        #[inline]
+        pub(super) const fn new() -> Self {
+            Self
+        }
+    }
+
+    #[inline]
+    pub(super) fn load<T, R, const N: usize>(
+        atomic: &AtomicVersioned<T, R, N>,
+        order: Ordering,
+    ) -> Versioned<T, N> {
+        assert!(order != Ordering::Release && order != Ordering::AcqRel);
+        // a CAS with identical current and new values never modifies the
+        // memory location but always returns its value
+        let dst = as_u128_ptr(atomic);
+        from_u128(unsafe { cmpxchg16b(dst, 0, 0, order, order) })
+    }
+
+    /// Pointer and version are always written at once, so no validation of
+    /// separate reads is required.
+    #[inline(always)]
+    pub(super) fn read_begin<T, R, const N: usize>(_: &AtomicVersioned<T, R, N>) -> usize {
+        0
+    }
+
+    #[inline(always)]
+    pub(super) fn read_validate<T, R, const N: usize>(
+        _: &AtomicVersioned<T, R, N>,
+        _: usize,
+    ) -> bool {
+        true
+    }
+
+    #[inline]
+    pub(super) fn compare_exchange<T, R, const N: usize>(
+        atomic: &AtomicVersioned<T, R, N>,
+        current: Versioned<T, N>,
+        new: MarkedPtr<T, N>,
+        (success, failure): (Ordering, Ordering),
+    ) -> Result<Versioned<T, N>, Versioned<T, N>> {
+        let dst = as_u128_ptr(atomic);
+        let old = into_u128(current);
+        let new = into_u128(Versioned { ptr: new, version: current.version.wrapping_add(1) });
+        match unsafe { cmpxchg16b(dst, old, new, success, failure) } {
+            prev if prev == old => Ok(current),
+            prev => Err(from_u128(prev)),
+        }
+    }
+
+    #[inline]
+    fn as_u128_ptr<T, R, const N: usize>(atomic: &AtomicVersioned<T, R, N>) -> *mut u128 {
+        // both (16-byte aligned) words consist only of atomic (interior mutable)
+        // fields, so writing through this pointer is sound
+        atomic as *const AtomicVersioned<T, R, N> as *mut u128
+    }
+
+    #[inline]
+    fn into_u128<T, const N: usize>(versioned: Versioned<T, N>) -> u128 {
+        versioned.ptr.into_usize() as u128 | (versioned.version as u128) << 64
+    }
+
+    #[inline]
+    fn from_u128<T, const N: usize>(value: u128) -> Versioned<T, N> {
+        Versioned { ptr: MarkedPtr::from_usize(value as usize), version: (value >> 64) as usize }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+// imp (sequence lock)
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(not(all(feature = "nightly", target_arch = "x86_64", target_feature = "cmpxchg16b")))]
+mod imp {
+    use core::hint;
+    use core::sync::atomic::{self, AtomicUsize, Ordering};
+
+    use conquer_pointer::MarkedPtr;
+
+    use super::{AtomicVersioned, Versioned};
+
+    /// The sequence lock, which is odd while a writer updates the pointer and
+    /// its version.
+    pub(super) struct Lock {
+        seq: AtomicUsize,
+    }
+
+    impl Lock {
+        #[inline]
+        pub(super) const fn new() -> Self {
+            Self { seq: AtomicUsize::new(0) }
+        }
+    }
+
+    #[inline]
+    pub(super) fn load<T, R, const N: usize>(
+        atomic: &AtomicVersioned<T, R, N>,
+        order: Ordering,
+    ) -> Versioned<T, N> {
+        loop {
+            let seq = read_begin(atomic);
+            let ptr = unsafe { atomic.ptr.as_raw() }.load(order);
+            let version = atomic.version.load(Ordering::Relaxed);
+            if read_validate(atomic, seq) {
+                return Versioned { ptr, version };
+            }
+        }
+    }
+
+    /// Waits until no writer holds the sequence lock and returns the (even)
+    /// sequence number.
+    #[inline]
+    pub(super) fn read_begin<T, R, const N: usize>(atomic: &AtomicVersioned<T, R, N>) -> usize {
+        loop {
+            let seq = atomic.lock.seq.load(Ordering::Acquire);
+            if seq & 1 == 0 {
+                return seq;
+            }
+
+            hint::spin_loop();
+        }
+    }
+
+    /// Returns `true` if no writer has acquired the sequence lock since
+    /// [`read_begin`] returned `seq`, i.e., if all reads in between were
+    /// consistent.
+    #[inline]
+    pub(super) fn read_validate<T, R, const N: usize>(
+        atomic: &AtomicVersioned<T, R, N>,
+        seq: usize,
+    ) -> bool {
+        atomic::fence(Ordering::Acquire);
+        atomic.lock.seq.load(Ordering::Relaxed) == seq
+    }
+
+    #[inline]
+    pub(super) fn compare_exchange<T, R, const N: usize>(
+        atomic: &AtomicVersioned<T, R, N>,
+        current: Versioned<T, N>,
+        new: MarkedPtr<T, N>,
+        (success, _): (Ordering, Ordering),
+    ) -> Result<Versioned<T, N>, Versioned<T, N>> {
+        let seq = lock(atomic);
+        // readers observing any of the following writes must also observe the
+        // odd sequence number
+        atomic::fence(Ordering::Release);
+        let ptr = unsafe { atomic.ptr.as_raw() };
+
+        let actual = Versioned {
+            ptr: ptr.load(Ordering::Relaxed),
+            version: atomic.version.load(Ordering::Relaxed),
+        };
+
+        // the sequence lock is acquired with acquire semantics, so the failure
+        // ordering is always satisfied
+        let res = if actual == current {
+            ptr.store(new, store_order(success));
+            atomic.version.store(current.version.wrapping_add(1), Ordering::Relaxed);
+            Ok(current)
+        } else {
+            Err(actual)
+        };
+
+        atomic.lock.seq.store(seq.wrapping_add(2), Ordering::Release);
+        res
+    }
+
+    /// Acquires the sequence lock and returns the (even) sequence number
+    /// before acquiring it.
+    #[inline]
+    fn lock<T, R, const N: usize>(atomic: &AtomicVersioned<T, R, N>) -> usize {
+        loop {
+            let seq = atomic.lock.seq.load(Ordering::Relaxed);
+            if seq & 1 == 0
+                && atomic
+                    .lock
+                    .seq
+                    .compare_exchange_weak(seq, seq + 1, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            {
+                return seq;
+            }
+
+            hint::spin_loop();
+        }
+    }
+
+    #[inline]
+    fn store_order(order: Ordering) -> Ordering {
+        match order {
+            Ordering::Relaxed | Ordering::Acquire => Ordering::Relaxed,
+            Ordering::Release | Ordering::AcqRel => Ordering::Release,
+            _ => Ordering::SeqCst,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering::{Acquire, Relaxed, SeqCst};
+
+    use conquer_pointer::MarkedPtr;
+
+    use super::{AtomicVersioned, Versioned};
+    use crate::atomic::Storable;
+    use crate::leak::{Guard, Leaking};
+    use crate::traits::{Protect, ProtectExt};
+    use crate::{Maybe, Owned};
+
+    type Atomic = AtomicVersioned<i32, Leaking, 0>;
+
+    #[test]
+    fn compare_exchange_versions() {
+        let atomic = Atomic::null();
+        let initial = atomic.load_raw(Relaxed);
+        assert_eq!(initial, Versioned { ptr: MarkedPtr::null(), version: 0 });
+
+        match atomic.compare_exchange(initial, Owned::new(1), (SeqCst, Relaxed)) {
+            Ok(Maybe::Null(tag)) => assert_eq!(tag, 0),
+            _ => panic!("compare-exchange with current snapshot must succeed"),
+        }
+
+        let first = atomic.load_raw(Relaxed);
+        assert!(!first.ptr.is_null());
+        assert_eq!(first.version, 1);
+
+        // a stale snapshot fails and returns the actual snapshot
+        match atomic.compare_exchange(initial, Owned::new(2), (SeqCst, Relaxed)) {
+            Err((actual, _)) => assert_eq!(actual, first),
+            Ok(_) => panic!("compare-exchange with stale snapshot must fail"),
+        }
+
+        match atomic.compare_exchange(first, Storable::null(), (SeqCst, Relaxed)) {
+            Ok(Maybe::Some(unlinked)) => assert_eq!(unlinked.as_marked_ptr(), first.ptr),
+            _ => panic!("compare-exchange with current snapshot must succeed"),
+        }
+
+        assert_eq!(atomic.load_raw(Relaxed), Versioned { ptr: MarkedPtr::null(), version: 2 });
+
+        // the pointer is again the same as in `initial`, but the version is not
+        let res = atomic.compare_exchange(initial, Storable::null(), (SeqCst, Relaxed));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn load_versions() {
+        let atomic = Atomic::new(Owned::new(1));
+        let mut guard = Guard;
+
+        let initial = atomic.load_raw(Relaxed);
+        let (protected, version) = atomic.load(&mut guard, Acquire);
+        assert_eq!(protected.into_marked_ptr(), initial.ptr);
+        assert_eq!(version, 0);
+        assert!(atomic.load_if_equal(initial, &mut guard, Acquire).is_ok());
+        assert!(guard.protect_if_equal_versioned(&atomic, initial, Acquire).is_ok());
+
+        // storing the same pointer again still increments the version
+        let store = Storable::from(atomic.load(&mut guard, Relaxed).0);
+        assert!(atomic.compare_exchange(initial, store, (SeqCst, Relaxed)).is_ok());
+        assert_eq!(atomic.load_raw(Relaxed), Versioned { ptr: initial.ptr, version: 1 });
+        assert!(atomic.load_if_equal(initial, &mut guard, Acquire).is_err());
+        assert!(guard.protect_fused_if_equal_versioned(&atomic, initial, Acquire).is_err());
+
+        let current = atomic.load_raw(Relaxed);
+        let fused = guard.protect_fused_if_equal_versioned(&atomic, current, Acquire).unwrap();
+        assert_eq!(fused.as_protected().into_marked_ptr(), current.ptr);
+    }
+}
@@ -0,0 +1,464 @@
+//! Types for bounding the amount of retired but not yet reclaimed records
+//! (garbage) of a reclamation mechanism.
+//!
+//! Under some reclamation schemes (e.g., epoch based reclamation) a single
+//! stalled thread can prevent the reclamation of *all* retired records, in
+//! which case the amount of garbage could grow without bounds.
+//! Reclamation mechanisms supporting a [`PendingLimit`] check it whenever a
+//! record is retired and apply the configured [`OverflowPolicy`], if retiring
+//! it would exceed the limit.
+//! Any thread state implementing [`ReclaimStats`] and [`ReclaimCollect`] can
+//! be configured with a limit by wrapping it in a [`Bounded`] thread state.
+
+use core::fmt;
+use core::hint;
+
+use crate::retired::Retired;
+use crate::stats::{ReclaimStats, Stats};
+use crate::traits::{ReclaimBase, ReclaimRef, ReclaimThreadState};
+use crate::Owned;
+
+// *************************************************************************************************
+// ReclaimCollect (trait)
+// *************************************************************************************************
+
+/// A trait for thread states of reclamation mechanisms, which are able to
+/// reclaim records inline on demand.
+pub trait ReclaimCollect {
+    /// Attempts to reclaim all retired records, which are no longer
+    /// protected.
+    fn collect(&self);
+}
+
+// *************************************************************************************************
+// OverflowPolicy
+// *************************************************************************************************
+
+/// The policy that is applied when a [`PendingLimit`] is exceeded.
+#[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum OverflowPolicy {
+    /// Attempt to reclaim records inline (once), but retire the record
+    /// regardless of the outcome.
+    Collect,
+    /// Repeatedly attempt to reclaim records inline until the amount of
+    /// pending records is below the limit again.
+    Spin,
+    /// Refuse to retire the record, so that
+    /// [`try_retire_record`][crate::ReclaimThreadState::try_retire_record]
+    /// returns an error.
+    Fail,
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for OverflowPolicy {
+    #[inline]
+    fn default() -> Self {
+        OverflowPolicy::Collect
+    }
+}
+
+// *************************************************************************************************
+// PendingLimit
+// *************************************************************************************************
+
+/// A cap on the number and/or combined size of pending (retired but not yet
+/// reclaimed) records of a reclamation mechanism instance.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct PendingLimit {
+    max_records: Option<usize>,
+    max_bytes: Option<usize>,
+    policy: OverflowPolicy,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl PendingLimit {
+    /// Creates a new limit that is never exceeded.
+    #[inline]
+    pub const fn unbounded() -> Self {
+        Self { max_records: None, max_bytes: None, policy: OverflowPolicy::Collect }
+    }
+
+    /// Returns the limit with its maximum number of pending records set to
+    /// `max`.
+    #[inline]
+    pub const fn with_max_records(self, max: usize) -> Self {
+        Self { max_records: Some(max), ..self }
+    }
+
+    /// Returns the limit with its maximum combined size in bytes of all
+    /// pending records set to `max`.
+    #[inline]
+    pub const fn with_max_bytes(self, max: usize) -> Self {
+        Self { max_bytes: Some(max), ..self }
+    }
+
+    /// Returns the limit with its [`OverflowPolicy`] set to `policy`.
+    #[inline]
+    pub const fn with_policy(self, policy: OverflowPolicy) -> Self {
+        Self { policy, ..self }
+    }
+
+    /// Returns the maximum number of pending records, if any.
+    #[inline]
+    pub const fn max_records(&self) -> Option<usize> {
+        self.max_records
+    }
+
+    /// Returns the maximum combined size in bytes of all pending records, if
+    /// any.
+    #[inline]
+    pub const fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// Returns the [`OverflowPolicy`].
+    #[inline]
+    pub const fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Returns `true` if the pending records reported by `stats` have reached
+    /// the limit, i.e., if retiring another record would exceed it.
+    #[inline]
+    pub fn is_exceeded(&self, stats: &Stats) -> bool {
+        let exceeds = |max: Option<usize>, curr| max.is_some_and(|max| curr >= max);
+        exceeds(self.max_records, stats.pending) || exceeds(self.max_bytes, stats.pending_bytes)
+    }
+
+    /// Checks the limit against the current statistics returned by `stats`
+    /// and applies the [`OverflowPolicy`], if it is exceeded, using `collect`
+    /// to reclaim records inline.
+    ///
+    /// This is meant to be called by reclamation mechanisms *before* retiring
+    /// a record.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the limit is exceeded and the policy is
+    /// [`Fail`][OverflowPolicy::Fail].
+    #[inline]
+    pub fn enforce(
+        &self,
+        stats: impl Fn() -> Stats,
+        mut collect: impl FnMut(),
+    ) -> Result<(), LimitExceeded> {
+        if !self.is_exceeded(&stats()) {
+            return Ok(());
+        }
+
+        match self.policy {
+            OverflowPolicy::Collect => collect(),
+            OverflowPolicy::Spin => loop {
+                collect();
+                if !self.is_exceeded(&stats()) {
+                    break;
+                }
+
+                hint::spin_loop();
+            },
+            OverflowPolicy::Fail => return Err(LimitExceeded),
+        }
+
+        Ok(())
+    }
+}
+
+// *************************************************************************************************
+// Bounded
+// *************************************************************************************************
+
+/// A thread state wrapper, which enforces a [`PendingLimit`] whenever a
+/// record is retired through it.
+///
+/// Since [`retire_record`][ReclaimThreadState::retire_record] can not fail,
+/// it retires records regardless of the limit, if its policy is
+/// [`Fail`][OverflowPolicy::Fail].
+/// Only [`try_retire_record`][ReclaimThreadState::try_retire_record] refuses
+/// to retire records in this case.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Bounded<S> {
+    state: S,
+    limit: PendingLimit,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<S> Bounded<S> {
+    /// Wraps the given thread `state`, enforcing the given `limit`.
+    #[inline]
+    pub const fn new(state: S, limit: PendingLimit) -> Self {
+        Self { state, limit }
+    }
+
+    /// Returns the enforced [`PendingLimit`].
+    #[inline]
+    pub fn limit(&self) -> PendingLimit {
+        self.limit
+    }
+
+    /// Sets the enforced [`PendingLimit`] to `limit`.
+    #[inline]
+    pub fn set_limit(&mut self, limit: PendingLimit) {
+        self.limit = limit;
+    }
+
+    /// Returns a reference to the wrapped thread state.
+    #[inline]
+    pub fn get_ref(&self) -> &S {
+        &self.state
+    }
+
+    /// Unwraps and returns the wrapped thread state.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.state
+    }
+}
+
+impl<S: ReclaimStats + ReclaimCollect> Bounded<S> {
+    /// Enforces the limit against the wrapped thread state's statistics.
+    #[inline]
+    fn enforce(&self) -> Result<(), LimitExceeded> {
+        self.limit.enforce(|| self.state.stats(), || self.state.collect())
+    }
+}
+
+/********** impl ReclaimThreadState ***************************************************************/
+
+unsafe impl<T, S> ReclaimThreadState<T> for Bounded<S>
+where
+    S: ReclaimThreadState<T> + ReclaimStats + ReclaimCollect,
+{
+    type Reclaim = S::Reclaim;
+    type Guard = S::Guard;
+
+    #[inline]
+    fn derived_from(&self, reclaimer: &impl ReclaimRef<T, Reclaim = Self::Reclaim>) -> bool {
+        self.state.derived_from(reclaimer)
+    }
+
+    #[inline]
+    fn build_guard(&self) -> Self::Guard {
+        self.state.build_guard()
+    }
+
+    #[inline]
+    fn alloc_owned<const N: usize>(&self, value: T) -> Owned<T, Self::Reclaim, N> {
+        self.state.alloc_owned(value)
+    }
+
+    #[inline]
+    unsafe fn retire_record(&self, retired: Retired<Self::Reclaim>) {
+        let _ = self.enforce();
+        self.state.retire_record(retired);
+    }
+
+    #[inline]
+    fn is_unprotected(&self, record: *const T) -> bool {
+        self.state.is_unprotected(record)
+    }
+
+    #[inline]
+    unsafe fn try_retire_record(
+        &self,
+        retired: Retired<Self::Reclaim>,
+    ) -> Result<(), RetireError<Self::Reclaim>> {
+        match self.enforce() {
+            Ok(_) => self.state.try_retire_record(retired),
+            Err(_) => Err(RetireError { retired }),
+        }
+    }
+}
+
+/********** impl ReclaimStats *********************************************************************/
+
+impl<S: ReclaimStats> ReclaimStats for Bounded<S> {
+    #[inline]
+    fn stats(&self) -> Stats {
+        self.state.stats()
+    }
+}
+
+/********** impl ReclaimCollect *******************************************************************/
+
+impl<S: ReclaimCollect> ReclaimCollect for Bounded<S> {
+    #[inline]
+    fn collect(&self) {
+        self.state.collect();
+    }
+}
+
+// *************************************************************************************************
+// LimitExceeded
+// *************************************************************************************************
+
+/// A type for indicating that a [`PendingLimit`] was exceeded.
+#[derive(Debug, Default, Copy, Clone, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct LimitExceeded;
+
+/********** impl Display **************************************************************************/
+
+impl fmt::Display for LimitExceeded {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("pending limit exceeded")
+    }
+}
+
+/********** impl Error ****************************************************************************/
+
+#[cfg(feature = "std")]
+impl std::error::Error for LimitExceeded {}
+
+// *************************************************************************************************
+// RetireError
+// *************************************************************************************************
+
+/// An error type indicating a failed
+/// [`try_retire_record`][crate::ReclaimThreadState::try_retire_record]
+/// operation.
+///
+/// The record that could not be retired is returned to the caller, who
+/// remains responsible for it.
+pub struct RetireError<R: ReclaimBase> {
+    /// The record that could not be retired.
+    pub retired: Retired<R>,
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<R: ReclaimBase> fmt::Debug for RetireError<R> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetireError").field("retired", &self.retired).finish()
+    }
+}
+
+/********** impl Display **************************************************************************/
+
+impl<R: ReclaimBase> fmt::Display for RetireError<R> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("failed to retire record: pending limit exceeded")
+    }
+}
+
+/********** impl Error ****************************************************************************/
+
+#[cfg(feature = "std")]
+impl<R: ReclaimBase> std::error::Error for RetireError<R> {}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use conquer_pointer::MarkedPtr;
+
+    use super::{Bounded, OverflowPolicy, PendingLimit, ReclaimCollect};
+    use crate::leak::{Guard, Leaking};
+    use crate::retired::Retired;
+    use crate::stats::{ReclaimStats, Stats};
+    use crate::traits::{ReclaimRef, ReclaimThreadState};
+    use crate::{Owned, Unlinked};
+
+    /// A thread state, which defers all retired records until collected.
+    #[derive(Default)]
+    struct Deferred {
+        pending: RefCell<Vec<Retired<Leaking>>>,
+        collects: Cell<usize>,
+    }
+
+    unsafe impl ReclaimThreadState<i32> for Deferred {
+        type Reclaim = Leaking;
+        type Guard = Guard;
+
+        fn derived_from(&self, _: &impl ReclaimRef<i32, Reclaim = Leaking>) -> bool {
+            true
+        }
+
+        fn build_guard(&self) -> Guard {
+            Guard
+        }
+
+        fn alloc_owned<const N: usize>(&self, value: i32) -> Owned<i32, Leaking, N> {
+            Owned::new(value)
+        }
+
+        unsafe fn retire_record(&self, retired: Retired<Leaking>) {
+            self.pending.borrow_mut().push(retired);
+        }
+    }
+
+    impl ReclaimStats for Deferred {
+        fn stats(&self) -> Stats {
+            let pending = self.pending.borrow().len();
+            Stats { pending, ..Default::default() }
+        }
+    }
+
+    impl ReclaimCollect for Deferred {
+        fn collect(&self) {
+            self.collects.set(self.collects.get() + 1);
+            self.pending.borrow_mut().clear();
+        }
+    }
+
+    fn retired(state: &impl ReclaimThreadState<i32, Reclaim = Leaking>) -> Retired<Leaking> {
+        let ptr: MarkedPtr<_, 0> = Owned::into_marked_ptr(state.alloc_owned(0));
+        unsafe { Unlinked::<_, Leaking, 0>::from_marked_ptr(ptr) }.into_retired()
+    }
+
+    fn bounded(policy: OverflowPolicy) -> Bounded<Deferred> {
+        let limit = PendingLimit::unbounded().with_max_records(1).with_policy(policy);
+        Bounded::new(Deferred::default(), limit)
+    }
+
+    #[test]
+    fn collect_policy() {
+        let state = bounded(OverflowPolicy::Collect);
+        for _ in 0..3 {
+            unsafe { state.try_retire_record(retired(&state)).unwrap() };
+        }
+
+        assert_eq!(state.get_ref().collects.get(), 2);
+        assert_eq!(state.stats().pending, 1);
+    }
+
+    #[test]
+    fn spin_policy() {
+        let pending = Cell::new(5);
+        let collects = Cell::new(0);
+        let limit = PendingLimit::unbounded().with_max_bytes(2).with_policy(OverflowPolicy::Spin);
+
+        let res = limit.enforce(
+            || Stats { pending_bytes: pending.get(), ..Default::default() },
+            || {
+                collects.set(collects.get() + 1);
+                pending.set(pending.get() - 1);
+            },
+        );
+
+        assert!(res.is_ok());
+        assert_eq!(collects.get(), 4);
+        assert_eq!(pending.get(), 1);
+    }
+
+    #[test]
+    fn fail_policy() {
+        let state = bounded(OverflowPolicy::Fail);
+        unsafe {
+            state.try_retire_record(retired(&state)).unwrap();
+            let err = state.try_retire_record(retired(&state)).unwrap_err();
+            assert_eq!(err.to_string(), "failed to retire record: pending limit exceeded");
+            assert_eq!(state.stats().pending, 1);
+
+            // infallible retirement ignores the limit
+            state.retire_record(retired(&state));
+        }
+
+        assert_eq!(state.get_ref().collects.get(), 0);
+        assert_eq!(state.stats().pending, 2);
+    }
+}
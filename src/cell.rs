@@ -0,0 +1,310 @@
+//! A high-level cell type for values that are atomically replaced as a whole.
+//!
+//! A [`ReclaimCell`] owns its current value as well as the reclaimer instance
+//! it uses for retiring replaced values, so that, unlike with a raw
+//! [`Atomic`], all operations on it can be safe.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::sync::atomic::Ordering::{AcqRel, Acquire, Relaxed};
+
+use conquer_pointer::MarkedNonNull;
+
+use crate::fused::FusedShared;
+use crate::traits::{Protect, ReclaimRef, ReclaimThreadState};
+use crate::{AtomicOwned, Maybe, Owned, Protected};
+
+type AssocGuard<T, R> = <<R as ReclaimRef<T>>::ThreadState as ReclaimThreadState<T>>::Guard;
+
+// *************************************************************************************************
+// ReclaimCell
+// *************************************************************************************************
+
+/// A cell holding a (never `null`) value, which can be atomically replaced
+/// while other threads are concurrently reading it.
+///
+/// Replaced values are automatically retired using the cell's reclaimer and
+/// the current value is de-allocated when the cell is dropped.
+/// All operations require a (thread-local) [`CellRef`] handle, which can be
+/// obtained through [`as_ref`][ReclaimCell::as_ref].
+pub struct ReclaimCell<T, R: ReclaimRef<T>> {
+    inner: AtomicOwned<T, R::Reclaim, 0>,
+    reclaimer: R,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, R: ReclaimRef<T> + Default> ReclaimCell<T, R> {
+    /// Creates a new cell holding `value` with a default reclaimer.
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self::with_reclaimer(value, Default::default())
+    }
+}
+
+impl<T, R: ReclaimRef<T>> ReclaimCell<T, R> {
+    /// Creates a new cell holding `value` with the given `reclaimer`.
+    #[inline]
+    pub fn with_reclaimer(value: T, reclaimer: R) -> Self {
+        let owned = reclaimer.alloc_owned(value);
+        Self { inner: AtomicOwned::new(owned), reclaimer }
+    }
+
+    /// Returns a new thread-local handle for accessing the cell.
+    #[inline]
+    pub fn as_ref(&self) -> CellRef<T, R> {
+        CellRef::new(self)
+    }
+
+    /// Returns a mutable reference to the current value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut().unwrap_or_else(|| unreachable!())
+    }
+
+    /// Consumes the cell and returns the current value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        let owned = self.inner.into_owned();
+        Owned::into_inner(owned.unwrap_or_else(|| unreachable!()))
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T, R: ReclaimRef<T>> fmt::Debug for ReclaimCell<T, R> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ReclaimCell {{ ... }}")
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<T: Default, R: ReclaimRef<T> + Default> Default for ReclaimCell<T, R> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+// *************************************************************************************************
+// CellRef
+// *************************************************************************************************
+
+/// A thread-local handle to a [`ReclaimCell`].
+pub struct CellRef<'c, T, R: ReclaimRef<T>> {
+    cell: &'c ReclaimCell<T, R>,
+    thread_state: R::ThreadState,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'c, T, R: ReclaimRef<T>> CellRef<'c, T, R> {
+    /// Creates a new [`CellRef`] from the given `cell` reference.
+    #[inline]
+    pub fn new(cell: &'c ReclaimCell<T, R>) -> Self {
+        Self { cell, thread_state: unsafe { cell.reclaimer.build_thread_state_unchecked() } }
+    }
+
+    /// Loads the current value and protects it from reclamation for as long
+    /// as the returned [`Loaded`] handle is alive.
+    #[inline]
+    pub fn load(&self) -> Loaded<'c, T, AssocGuard<T, R>> {
+        let mut guard = self.thread_state.build_guard();
+        let protected = self.cell.inner.load(&mut guard, Acquire).into_marked_ptr();
+        // safety: the cell's value is never null
+        unsafe { Loaded::new(guard, MarkedNonNull::new_unchecked(protected)) }
+    }
+
+    /// Loads the current value and protects it from reclamation using the
+    /// given `guard`.
+    ///
+    /// # Safety
+    ///
+    /// `guard` must have been built by a thread state derived from the cell's
+    /// reclaimer, e.g., through [`build_guard`][CellRef::build_guard].
+    #[inline]
+    pub unsafe fn load_with<'g>(
+        &self,
+        guard: &'g mut impl Protect<T, Reclaim = R::Reclaim>,
+    ) -> &'g T {
+        self.cell.inner.load(guard, Acquire).deref()
+    }
+
+    /// Builds a new guard suitable for [`load_with`][CellRef::load_with].
+    #[inline]
+    pub fn build_guard(&self) -> AssocGuard<T, R> {
+        self.thread_state.build_guard()
+    }
+
+    /// Replaces the current value with `value` and retires the previous one.
+    #[inline]
+    pub fn store(&self, value: T) {
+        let owned = self.thread_state.alloc_owned(value);
+        if let Maybe::Some(unlinked) = self.cell.inner.swap_owned(owned, AcqRel) {
+            // safety: the cell is the only owner of its value
+            unsafe { self.thread_state.retire_record(unlinked.into_retired()) };
+        }
+    }
+
+    /// Replaces the current value with `value` and retires the previous one,
+    /// which is returned protected from reclamation.
+    #[inline]
+    pub fn swap(&self, value: T) -> Loaded<'c, T, AssocGuard<T, R>> {
+        let mut value = Some(self.thread_state.alloc_owned(value));
+        self.update(|_, prev| prev.or_else(|| value.take()))
+    }
+
+    /// Replaces the current value with the value returned by `func` and
+    /// retires the previous one, which is returned protected from
+    /// reclamation.
+    ///
+    /// `func` is called with the current value and may be called more than
+    /// once, if the value is concurrently replaced by other threads.
+    #[inline]
+    pub fn rcu(&self, mut func: impl FnMut(&T) -> T) -> Loaded<'c, T, AssocGuard<T, R>> {
+        self.update(|curr, _| {
+            // safety: the value is never null and was loaded with acquire semantics
+            let curr = unsafe { curr.deref() };
+            Some(self.thread_state.alloc_owned(func(curr)))
+        })
+    }
+
+    /// Replaces the current value with `new`, if it is still the same as the
+    /// `current` loaded value, and retires the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the current value has been replaced since `current` was
+    /// loaded, in which case `new` is returned.
+    #[inline]
+    pub fn compare_and_swap(
+        &self,
+        current: &Loaded<'c, T, AssocGuard<T, R>>,
+        new: T,
+    ) -> Result<(), T> {
+        let owned = self.thread_state.alloc_owned(new);
+        let current = current.fused.as_shared();
+        match self.cell.inner.compare_exchange_owned(current, owned, (AcqRel, Relaxed)) {
+            Ok(unlinked) => {
+                // safety: the cell is the only owner of its value
+                unsafe { self.thread_state.retire_record(unlinked.into_retired()) };
+                Ok(())
+            }
+            Err(err) => Err(Owned::into_inner(err.input)),
+        }
+    }
+
+    #[inline]
+    fn update(
+        &self,
+        func: impl FnMut(
+            Protected<T, R::Reclaim, 0>,
+            Option<Owned<T, R::Reclaim, 0>>,
+        ) -> Option<Owned<T, R::Reclaim, 0>>,
+    ) -> Loaded<'c, T, AssocGuard<T, R>> {
+        let mut guard = self.thread_state.build_guard();
+        unsafe {
+            // safety: the cell is the only owner of its value
+            let prev = self.cell.inner.as_atomic().update(
+                &mut guard,
+                &self.thread_state,
+                Acquire,
+                (AcqRel, Relaxed),
+                func,
+            );
+
+            let prev = prev.unwrap_or_else(|_| unreachable!()).into_marked_ptr();
+            Loaded::new(guard, MarkedNonNull::new_unchecked(prev))
+        }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T, R: ReclaimRef<T>> fmt::Debug for CellRef<'_, T, R> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CellRef {{ ... }}")
+    }
+}
+
+// *************************************************************************************************
+// Loaded
+// *************************************************************************************************
+
+/// A value loaded from a [`ReclaimCell`], which is protected from reclamation
+/// for as long as the `Loaded` handle is alive.
+pub struct Loaded<'c, T, G> {
+    fused: FusedShared<T, G, 0>,
+    _marker: PhantomData<&'c T>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, G: Protect<T>> Loaded<'_, T, G> {
+    /// Consumes the handle and returns the contained guard instance.
+    #[inline]
+    pub fn into_guard(self) -> G {
+        self.fused.into_guard()
+    }
+
+    #[inline]
+    unsafe fn new(guard: G, shared: MarkedNonNull<T, 0>) -> Self {
+        Self { fused: FusedShared { guard, shared }, _marker: PhantomData }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T: fmt::Debug, G: Protect<T>> fmt::Debug for Loaded<'_, T, G> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Loaded").field(&**self).finish()
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<T, G: Protect<T>> Deref for Loaded<'_, T, G> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // safety: the value is protected by the guard and was loaded with acquire semantics
+        unsafe { self.fused.as_shared().as_ref() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::leak::Leaking;
+
+    type ReclaimCell = super::ReclaimCell<String, Leaking>;
+
+    #[test]
+    fn replace_values() {
+        let mut cell = ReclaimCell::new(String::from("a"));
+
+        {
+            let cell = cell.as_ref();
+            let first = cell.load();
+            assert_eq!(&*first, "a");
+
+            cell.store(String::from("b"));
+            assert_eq!(&*cell.swap(String::from("c")), "b");
+            assert_eq!(&*cell.rcu(|curr| format!("{}d", curr)), "c");
+            assert_eq!(&*cell.load(), "cd");
+
+            assert_eq!(cell.compare_and_swap(&first, String::from("x")), Err(String::from("x")));
+            let curr = cell.load();
+            assert_eq!(cell.compare_and_swap(&curr, String::from("e")), Ok(()));
+            assert_eq!(&*first, "a");
+        }
+
+        cell.get_mut().push('!');
+        assert_eq!(cell.into_inner(), "e!");
+    }
+}
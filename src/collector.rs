@@ -0,0 +1,432 @@
+//! A dedicated background thread for reclaiming retired records.
+//!
+//! Reclaiming a record runs the [`Drop`] implementation of its type, which
+//! may be arbitrarily expensive.
+//! Reclamation mechanisms can hand batches of reclaimable records to a
+//! [`Collector`] instead of reclaiming them inline, which keeps these costs
+//! out of latency-sensitive threads.
+//!
+//! A collector can be spawned with a [`ReclaimObserver`], which is notified
+//! whenever a record is reclaimed by the collector.
+//! Retiring a record is not reported by the collector, since this is up to
+//! the reclamation mechanism that submits it.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle, Thread};
+
+use crate::observer::{NoopObserver, ReclaimObserver};
+use crate::retired::{AtomicRetiredList, LinkedHeader, Retired, RetiredList};
+use crate::traits::ReclaimBase;
+
+// *************************************************************************************************
+// Collector
+// *************************************************************************************************
+
+/// An owning handle to a background thread, which reclaims all records
+/// submitted to it through any of its [`CollectorHandle`]s.
+///
+/// Dropping the `Collector` shuts down and joins the background thread after
+/// all records submitted until then have been reclaimed.
+/// Records submitted through handles outliving the `Collector` are
+/// reclaimed when the last handle is dropped, so no garbage is ever leaked.
+pub struct Collector<R: ReclaimBase + 'static, O: ReclaimObserver = NoopObserver>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    handle: CollectorHandle<R, O>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<R: ReclaimBase + 'static> Collector<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    /// Spawns a new background collector thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OS fails to create the thread.
+    #[inline]
+    pub fn spawn() -> Self {
+        Self::spawn_with_observer(NoopObserver)
+    }
+}
+
+impl<R: ReclaimBase + 'static, O: ReclaimObserver> Collector<R, O>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    /// Spawns a new background collector thread, which notifies `observer`
+    /// whenever a record is reclaimed by it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OS fails to create the thread.
+    #[inline]
+    pub fn spawn_with_observer(observer: O) -> Self
+    where
+        O: Send + Sync + 'static,
+    {
+        let queue = Arc::new(Queue {
+            list: AtomicRetiredList::new(),
+            shutdown: AtomicBool::new(false),
+            observer,
+        });
+        let thread = {
+            let queue = Arc::clone(&queue);
+            thread::Builder::new()
+                .name(String::from("conquer-reclaim-collector"))
+                .spawn(move || queue.run())
+                .expect("failed to spawn collector thread")
+        };
+
+        Self {
+            handle: CollectorHandle { queue, thread: thread.thread().clone() },
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns a new handle for submitting records to the collector.
+    #[inline]
+    pub fn handle(&self) -> CollectorHandle<R, O> {
+        self.handle.clone()
+    }
+
+    /// Returns a reference to the collector's observer.
+    #[inline]
+    pub fn observer(&self) -> &O {
+        &self.handle.queue.observer
+    }
+
+    /// Shuts down the collector thread and waits for it to reclaim all records
+    /// submitted until then.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the collector thread panicked, i.e. if the [`Drop`]
+    /// implementation of any reclaimed record panicked.
+    #[inline]
+    pub fn shutdown(mut self) -> thread::Result<()> {
+        self.join()
+    }
+
+    #[inline]
+    fn join(&mut self) -> thread::Result<()> {
+        match self.thread.take() {
+            Some(thread) => {
+                self.handle.queue.shutdown.store(true, Ordering::Release);
+                thread.thread().unpark();
+                thread.join()
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<R: ReclaimBase + 'static, O: ReclaimObserver> fmt::Debug for Collector<R, O>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Collector").field("thread", &self.handle.thread).finish()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<R: ReclaimBase + 'static, O: ReclaimObserver> Drop for Collector<R, O>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    #[inline]
+    fn drop(&mut self) {
+        let _ = self.join();
+    }
+}
+
+// *************************************************************************************************
+// CollectorHandle
+// *************************************************************************************************
+
+/// A handle for submitting reclaimable records to a [`Collector`].
+pub struct CollectorHandle<R: ReclaimBase, O: ReclaimObserver = NoopObserver>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    queue: Arc<Queue<R, O>>,
+    thread: Thread,
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl<R: ReclaimBase, O: ReclaimObserver> Clone for CollectorHandle<R, O>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { queue: Arc::clone(&self.queue), thread: self.thread.clone() }
+    }
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<R: ReclaimBase, O: ReclaimObserver> CollectorHandle<R, O>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    /// Submits a single `retired` record for reclamation.
+    ///
+    /// # Safety
+    ///
+    /// The record must be reclaimable, i.e., all requirements of
+    /// [`Retired::reclaim`] must be met, except that it may be reclaimed by
+    /// another thread.
+    /// Since the record is dropped by the collector thread, its type must
+    /// also be [`Send`].
+    #[inline]
+    pub unsafe fn submit(&self, retired: Retired<R>) {
+        self.queue.list.push(retired);
+        self.thread.unpark();
+    }
+
+    /// Submits an entire `batch` of records for reclamation.
+    ///
+    /// # Safety
+    ///
+    /// All records in `batch` must be reclaimable, i.e., all requirements of
+    /// [`Retired::reclaim`] must be met, except that they may be reclaimed by
+    /// another thread.
+    /// Since the records are dropped by the collector thread, their types
+    /// must also be [`Send`].
+    #[inline]
+    pub unsafe fn submit_batch(&self, batch: RetiredList<R>) {
+        if !batch.is_empty() {
+            self.queue.list.push_list(batch);
+            self.thread.unpark();
+        }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<R: ReclaimBase, O: ReclaimObserver> fmt::Debug for CollectorHandle<R, O>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CollectorHandle").field("thread", &self.thread).finish()
+    }
+}
+
+// *************************************************************************************************
+// Queue
+// *************************************************************************************************
+
+/// The queue of reclaimable records shared by a collector thread and all of
+/// its handles.
+struct Queue<R: ReclaimBase, O: ReclaimObserver>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    list: AtomicRetiredList<R>,
+    shutdown: AtomicBool,
+    observer: O,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<R: ReclaimBase, O: ReclaimObserver> Queue<R, O>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    /// The collector thread's main loop.
+    #[inline]
+    fn run(&self) {
+        loop {
+            let batch = self.list.steal_all();
+            if batch.is_empty() {
+                // all records submitted before the shutdown flag was set are
+                // visible after loading it, so one final pass is sufficient
+                if self.shutdown.load(Ordering::Acquire) {
+                    unsafe { self.reclaim_all(self.list.steal_all()) };
+                    return;
+                }
+
+                thread::park();
+                continue;
+            }
+
+            // safety: all records were submitted as reclaimable
+            unsafe { self.reclaim_all(batch) };
+        }
+    }
+
+    /// Reclaims all records in `batch` and notifies the observer about each
+    /// of them.
+    #[inline]
+    unsafe fn reclaim_all(&self, mut batch: RetiredList<R>) {
+        for mut retired in batch.drain() {
+            self.observer.on_reclaim(retired.as_ptr(), retired.record_size());
+            retired.reclaim();
+        }
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<R: ReclaimBase, O: ReclaimObserver> Drop for Queue<R, O>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    #[inline]
+    fn drop(&mut self) {
+        // reclaims any records submitted through handles after the collector
+        // thread has been shut down
+        unsafe { self.reclaim_all(self.list.steal_all()) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use conquer_pointer::MarkedPtr;
+
+    use super::Collector;
+    use crate::observer::ReclaimObserver;
+    use crate::retired::{LinkedHeader, Retired, RetiredLink, RetiredList};
+    use crate::stats::{AtomicStats, ReclaimStats};
+    use crate::{Owned, Unlinked};
+
+    #[derive(Default)]
+    struct Header {
+        link: RetiredLink,
+    }
+
+    unsafe impl LinkedHeader for Header {
+        fn retired_link(&self) -> &RetiredLink {
+            &self.link
+        }
+    }
+
+    struct Collected;
+
+    crate::impl_erased_reclaim!(compact Collected, Header);
+
+    struct DropCounting(&'static AtomicUsize);
+
+    impl Drop for DropCounting {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn retired(drop_count: &'static AtomicUsize) -> Retired<Collected> {
+        let owned = Owned::<_, Collected, 0>::new(DropCounting(drop_count));
+        let ptr: MarkedPtr<_, 0> = Owned::into_marked_ptr(owned);
+        unsafe { Unlinked::<_, Collected, 0>::from_marked_ptr(ptr) }.into_retired()
+    }
+
+    #[test]
+    fn observe_collected() {
+        static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+        static STATS: AtomicStats = AtomicStats::new();
+
+        let observer: &'static (dyn ReclaimObserver + Sync) = &STATS;
+        let collector = Collector::<Collected, _>::spawn_with_observer(observer);
+        let handle = collector.handle();
+
+        // retiring is reported by the reclamation mechanism, not the collector
+        let retired = || {
+            let retired = retired(&DROP_COUNT);
+            STATS.on_retire(retired.as_ptr(), retired.record_size());
+            retired
+        };
+
+        let mut batch = RetiredList::new();
+        batch.push(retired());
+        batch.push(retired());
+        unsafe {
+            handle.submit(retired());
+            handle.submit_batch(batch);
+        }
+
+        collector.shutdown().unwrap();
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 3);
+
+        let stats = STATS.stats();
+        assert_eq!((stats.retired, stats.reclaimed, stats.pending), (3, 3, 0));
+        assert_eq!(stats.pending_bytes, 0);
+    }
+
+    #[test]
+    fn drop_joins() {
+        static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let collector = Collector::<Collected>::spawn();
+        unsafe { collector.handle().submit(retired(&DROP_COUNT)) };
+        drop(collector);
+
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn shutdown_panicked() {
+        struct Panicking;
+
+        impl Drop for Panicking {
+            fn drop(&mut self) {
+                panic!("panicking drop");
+            }
+        }
+
+        let collector = Collector::<Collected>::spawn();
+        let owned = Owned::<_, Collected, 0>::new(Panicking);
+        let ptr: MarkedPtr<_, 0> = Owned::into_marked_ptr(owned);
+        unsafe {
+            let retired = Unlinked::<_, Collected, 0>::from_marked_ptr(ptr).into_retired();
+            collector.handle().submit(retired);
+        }
+
+        assert!(collector.shutdown().is_err());
+    }
+
+    #[test]
+    fn reclaim_leftovers() {
+        static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let collector = Collector::<Collected>::spawn();
+        let handle = collector.handle();
+        collector.shutdown().unwrap();
+
+        // records submitted after the shutdown are reclaimed by the last handle
+        unsafe { handle.submit(retired(&DROP_COUNT)) };
+        let clone = handle.clone();
+        drop(handle);
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 0);
+
+        drop(clone);
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,375 @@
+//! Reference counted handles to reclaimable records, which can keep a value
+//! alive beyond the scope of the guard that protected it.
+//!
+//! Reclamation mechanisms opt into this functionality by reserving a
+//! [`RefCount`] in their [`Header`][ReclaimBase::Header] type (see
+//! [`CountedHeader`]) and reclaiming their records through [`release`].
+//! Every record starts with a single reference, which is owned by the
+//! reclamation mechanism and given up once the record is reclaimed, while any
+//! [`Shared`][crate::Shared] reference to such a record can be upgraded into
+//! an additional reference in the form of a [`ReclaimArc`].
+//! The record is only de-allocated once all references have been released.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+use core::ptr::NonNull;
+use core::sync::atomic::{self, AtomicUsize, Ordering};
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
+use crate::alias::AssocRecord;
+use crate::traits::{Reclaim, ReclaimBase};
+
+// *************************************************************************************************
+// CountedHeader (trait)
+// *************************************************************************************************
+
+/// A trait for [`Header`][ReclaimBase::Header] types, which reserve a
+/// [`RefCount`] for counting the references to their records.
+///
+/// # Safety
+///
+/// The returned reference count must be part of the header itself and must
+/// be initialized with [`RefCount::new`] when the header's record is
+/// allocated.
+/// Furthermore, every reclamation mechanism using the header type must
+/// reclaim its records exclusively through [`release`] and must not
+/// de-allocate them in any other way.
+/// Note, that this does not extend to records de-allocated through an
+/// [`Owned`][crate::Owned] (e.g., when it is dropped), which does not check
+/// the reference count, so callers of [`upgrade`][crate::Shared::upgrade]
+/// must ensure that upgraded records are only ever freed through retirement.
+pub unsafe trait CountedHeader {
+    /// Returns a reference to the header's reference count.
+    fn ref_count(&self) -> &RefCount;
+}
+
+// *************************************************************************************************
+// RefCount
+// *************************************************************************************************
+
+/// A reference count for a reclaimable record, which is stored in the
+/// record's header.
+pub struct RefCount {
+    count: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl RefCount {
+    /// Creates a new [`RefCount`] with a single reference, which is owned by
+    /// the reclamation mechanism.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { count: AtomicUsize::new(1) }
+    }
+
+    /// Returns the current number of references.
+    #[inline]
+    pub fn load(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Acquires an additional reference.
+    #[inline]
+    fn increment(&self) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Releases a reference and returns `true`, if it was the last one.
+    #[inline]
+    fn decrement(&self) -> bool {
+        if self.count.fetch_sub(1, Ordering::Release) == 1 {
+            atomic::fence(Ordering::Acquire);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for RefCount {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl fmt::Debug for RefCount {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RefCount").field("count", &self.load()).finish()
+    }
+}
+
+// *************************************************************************************************
+// ReclaimArc
+// *************************************************************************************************
+
+/// A reference counted handle to a reclaimable record, which keeps the
+/// record's value alive independently of any guard.
+///
+/// A `ReclaimArc` is created by [upgrading][crate::Shared::upgrade] a
+/// [`Shared`][crate::Shared] reference.
+/// The record is de-allocated once it has been reclaimed and all handles to it
+/// have been dropped, whichever comes last.
+/// Records which are never retired (e.g., because their owning
+/// [`Atomic`][crate::Atomic] is dropped) are never de-allocated by the last
+/// handle either.
+pub struct ReclaimArc<T, R: Reclaim<T>>
+where
+    R::Header: CountedHeader,
+{
+    ptr: NonNull<T>,
+    _marker: PhantomData<(T, R)>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<T, R: Reclaim<T>> ReclaimArc<T, R>
+where
+    R::Header: CountedHeader,
+{
+    /// Returns the current number of references to the record, including the
+    /// one owned by the reclamation mechanism, if it has not yet reclaimed the
+    /// record.
+    #[inline]
+    pub fn ref_count(this: &Self) -> usize {
+        this.header().ref_count().load()
+    }
+
+    /// Returns `true` if both handles reference the same record.
+    #[inline]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr == other.ptr
+    }
+
+    /// Returns a raw pointer to the referenced value.
+    #[inline]
+    pub fn as_ptr(this: &Self) -> *const T {
+        this.ptr.as_ptr()
+    }
+
+    /// Acquires an additional reference to the record containing the value
+    /// pointed to by `ptr` and returns it as a new handle.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point at the value of a record allocated for `R`, which has
+    /// not yet been reclaimed and is protected from reclamation for the
+    /// duration of the call.
+    #[inline]
+    pub(crate) unsafe fn acquire(ptr: NonNull<T>) -> Self {
+        let arc = Self { ptr, _marker: PhantomData };
+        arc.header().ref_count().increment();
+        arc
+    }
+
+    #[inline]
+    fn header(&self) -> &R::Header {
+        unsafe { &*AssocRecord::<T, R>::header_from_data(self.ptr.as_ptr()) }
+    }
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl<T, R: Reclaim<T>> Clone for ReclaimArc<T, R>
+where
+    R::Header: CountedHeader,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        unsafe { Self::acquire(self.ptr) }
+    }
+}
+
+/********** impl Send & Sync **********************************************************************/
+
+unsafe impl<T, R> Send for ReclaimArc<T, R>
+where
+    T: Send + Sync,
+    R: Reclaim<T>,
+    R::Header: CountedHeader,
+{
+}
+
+unsafe impl<T, R> Sync for ReclaimArc<T, R>
+where
+    T: Send + Sync,
+    R: Reclaim<T>,
+    R::Header: CountedHeader,
+{
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T: fmt::Debug, R: Reclaim<T>> fmt::Debug for ReclaimArc<T, R>
+where
+    R::Header: CountedHeader,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ReclaimArc").field(&**self).finish()
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<T, R: Reclaim<T>> Deref for ReclaimArc<T, R>
+where
+    R::Header: CountedHeader,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<T, R: Reclaim<T>> Drop for ReclaimArc<T, R>
+where
+    R::Header: CountedHeader,
+{
+    #[inline]
+    fn drop(&mut self) {
+        if self.header().ref_count().decrement() {
+            // safety: the reclamation mechanism and all other handles have released the record
+            unsafe {
+                mem::drop(Box::from_raw(AssocRecord::<T, R>::record_from_data(self.ptr.as_ptr())))
+            };
+        }
+    }
+}
+
+// *************************************************************************************************
+// release
+// *************************************************************************************************
+
+/// Releases the reclamation mechanism's reference to the `retired` record and
+/// de-allocates it, if there are no [`ReclaimArc`] handles left referencing
+/// it.
+///
+/// Reclamation mechanisms using a [`CountedHeader`] must call this function
+/// in their implementation of [`ReclaimBase::reclaim`].
+/// Only *typed* mechanisms (i.e., with [`Retired`][ReclaimBase::Retired]
+/// being the actual record type `T`) are supported, since the record's layout
+/// is derived from its retired pointer.
+///
+/// # Safety
+///
+/// The same safety requirements as for [`ReclaimBase::reclaim`] apply.
+#[inline]
+pub unsafe fn release<T, R: ReclaimBase<Retired = T>>(retired: *mut T)
+where
+    R::Header: CountedHeader,
+{
+    let header = &*AssocRecord::<T, R>::header_from_data(retired);
+    if header.ref_count().decrement() {
+        mem::drop(Box::from_raw(AssocRecord::<T, R>::record_from_data(retired)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use conquer_pointer::MarkedNonNull;
+
+    use super::{CountedHeader, ReclaimArc, RefCount};
+    use crate::traits::{Reclaim, ReclaimBase};
+    use crate::{Atomic, Maybe, Owned, Shared, Storable};
+
+    static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    struct DropCounting(i32);
+
+    impl Drop for DropCounting {
+        fn drop(&mut self) {
+            DROP_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[derive(Default)]
+    struct Header(RefCount);
+
+    unsafe impl CountedHeader for Header {
+        fn ref_count(&self) -> &RefCount {
+            &self.0
+        }
+    }
+
+    struct Counted;
+
+    unsafe impl ReclaimBase for Counted {
+        type Header = Header;
+        type Retired = DropCounting;
+
+        unsafe fn reclaim(retired: *mut DropCounting) {
+            super::release::<_, Self>(retired);
+        }
+    }
+
+    unsafe impl Reclaim<DropCounting> for Counted {
+        unsafe fn retire(ptr: *mut DropCounting) -> *mut DropCounting {
+            ptr
+        }
+    }
+
+    #[test]
+    fn upgrade_outlives_reclamation() {
+        let atomic: Atomic<DropCounting, Counted, 0> = Atomic::new(Owned::new(DropCounting(1)));
+
+        let arc = unsafe {
+            let ptr = MarkedNonNull::new_unchecked(atomic.load_raw(Ordering::Relaxed));
+            Shared::<_, Counted, 0>::from_marked_non_null(ptr).upgrade()
+        };
+
+        let clone = arc.clone();
+        assert_eq!(ReclaimArc::ref_count(&arc), 3);
+
+        let mut retired = match atomic.swap(Storable::null(), Ordering::Relaxed) {
+            Maybe::Some(unlinked) => unlinked.into_retired(),
+            Maybe::Null(_) => unreachable!(),
+        };
+
+        unsafe { retired.reclaim() };
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 0);
+        assert_eq!(clone.0, 1);
+
+        drop(arc);
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 0);
+        drop(clone);
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn upgrade_outlives_atomic() {
+        let arc = {
+            let atomic: Atomic<DropCounting, Counted, 0> = Atomic::new(Owned::new(DropCounting(2)));
+            unsafe {
+                let ptr = MarkedNonNull::new_unchecked(atomic.load_raw(Ordering::Relaxed));
+                Shared::<_, Counted, 0>::from_marked_non_null(ptr).upgrade()
+            }
+        };
+
+        // dropping the atomic at the end of the block does not free (i.e., leaks) the record
+        assert_eq!(arc.0, 2);
+        assert_eq!(ReclaimArc::ref_count(&arc), 2);
+
+        // the never retired record remains valid for all handles
+        let clone = arc.clone();
+        drop(arc);
+        assert_eq!(clone.0, 2);
+        assert_eq!(ReclaimArc::ref_count(&clone), 2);
+    }
+}
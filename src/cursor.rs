@@ -0,0 +1,415 @@
+//! A generic cursor for traversing singly-linked structures of nodes with
+//! hand-over-hand protection.
+//!
+//! Traversals of lock-free linked lists generally follow the same pattern:
+//! Every node is protected when it is loaded from its predecessor's link,
+//! after which the link is re-checked to validate that the node has not been
+//! unlinked in the meantime, otherwise the traversal has to be restarted from
+//! the head.
+//! The [`LinkCursor`] implements this pattern for any node type implementing
+//! [`Linked`] and additionally helps unlinking (and retiring) any logically
+//! deleted nodes it encounters.
+//!
+//! A node is considered to be *logically deleted*, when its own link is tagged
+//! with its [`DELETED`][Linked::DELETED] tag (see
+//! [`delete`][LinkCursor::delete]).
+
+use core::fmt;
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+
+use conquer_pointer::{MarkedNonNull, MarkedPtr};
+
+use crate::atomic::Atomic;
+use crate::tag::{self, Tag};
+use crate::traits::{ProtectMany, ReclaimThreadState};
+use crate::{Owned, Protected, Shared};
+
+// *************************************************************************************************
+// Linked (trait)
+// *************************************************************************************************
+
+/// A trait for node types of singly-linked structures.
+pub trait Linked<R, const N: usize>: Sized {
+    /// The (typed) tag of a node's link.
+    type Mark: Tag + PartialEq;
+    /// The tag marking a node as logically deleted, which must not be
+    /// represented by the raw tag value 0.
+    const DELETED: Self::Mark;
+
+    /// Returns a reference to the node's link to its successor.
+    fn link(&self) -> &Atomic<Self, R, N>;
+}
+
+// *************************************************************************************************
+// Step
+// *************************************************************************************************
+
+/// The outcome of advancing a [`LinkCursor`].
+#[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Step {
+    /// The cursor has advanced to the next node (or the end).
+    Next,
+    /// The cursor has already been positioned at the end.
+    End,
+    /// The cursor's position could not be validated and the cursor has been
+    /// reset to the first node.
+    Restart,
+}
+
+// *************************************************************************************************
+// LinkCursor
+// *************************************************************************************************
+
+/// A cursor traversing the nodes reachable from a `head` link with
+/// hand-over-hand protection.
+///
+/// The cursor uses three protection slots of its guard `G` for protecting the
+/// predecessor ([`PREV`][LinkCursor::PREV]), the current node
+/// ([`CURR`][LinkCursor::CURR]) and its successor
+/// ([`NEXT`][LinkCursor::NEXT]) and never rests on a logically deleted node.
+pub struct LinkCursor<'a, T, S: ReclaimThreadState<T>, G, const N: usize> {
+    head: &'a Atomic<T, S::Reclaim, N>,
+    thread_state: &'a S,
+    prev: NonNull<Atomic<T, S::Reclaim, N>>,
+    curr: MarkedPtr<T, N>,
+    next: MarkedPtr<T, N>,
+    guards: G,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'a, T, S, G, const N: usize> LinkCursor<'a, T, S, G, N>
+where
+    T: Linked<S::Reclaim, N>,
+    S: ReclaimThreadState<T>,
+    G: ProtectMany<T, Reclaim = S::Reclaim>,
+{
+    /// The slot protecting the predecessor of the current node.
+    pub const PREV: usize = 0;
+    /// The slot protecting the current node.
+    pub const CURR: usize = 1;
+    /// The slot protecting the successor of the current node.
+    pub const NEXT: usize = 2;
+
+    const ACQ: Ordering = Ordering::Acquire;
+    const REL_RLX: (Ordering, Ordering) = (Ordering::Release, Ordering::Relaxed);
+
+    /// Creates a new cursor positioned at the first node reachable from
+    /// `head`.
+    ///
+    /// # Safety
+    ///
+    /// All nodes must be published (i.e., stored into any link) with (at
+    /// least) [`Release`][Ordering::Release] semantics and every node must
+    /// only ever be linked from (at most) one other link, so that unlinked
+    /// nodes can be safely retired.
+    /// Also, `thread_state` must be derived from the same reclaimer instance
+    /// as the guards and all nodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `G` has less than three protection slots or if
+    /// [`DELETED`][Linked::DELETED] is represented by the raw tag value 0.
+    #[inline]
+    pub unsafe fn new(head: &'a Atomic<T, S::Reclaim, N>, guards: G, thread_state: &'a S) -> Self {
+        assert!(G::SLOTS >= 3, "cursor requires (at least) three protection slots");
+        assert_ne!(
+            tag::into_raw::<T::Mark, N>(T::DELETED),
+            0,
+            "deleted nodes must be marked with a non-zero tag"
+        );
+        let mut cursor = Self {
+            head,
+            thread_state,
+            prev: NonNull::from(head),
+            curr: MarkedPtr::null(),
+            next: MarkedPtr::null(),
+            guards,
+        };
+
+        cursor.reset();
+        cursor
+    }
+
+    /// Returns a reference to the link the current node has been loaded from.
+    #[inline]
+    pub fn prev(&self) -> &Atomic<T, S::Reclaim, N> {
+        // safety: the link is either the head or part of the protected predecessor
+        unsafe { self.prev.as_ref() }
+    }
+
+    /// Returns the current node or [`None`], if the cursor is positioned at
+    /// the end.
+    #[inline]
+    pub fn curr(&self) -> Option<Shared<T, S::Reclaim, N>> {
+        MarkedNonNull::new(self.curr).ok().map(|curr| unsafe { Shared::from_marked_non_null(curr) })
+    }
+
+    /// Returns the (protected) successor of the current node.
+    #[inline]
+    pub fn next(&self) -> Protected<T, S::Reclaim, N> {
+        unsafe { Protected::from_marked_ptr(self.next) }
+    }
+
+    /// Returns a reference to the current node or [`None`], if the cursor is
+    /// positioned at the end.
+    #[inline]
+    pub fn curr_ref(&self) -> Option<&T> {
+        // safety: the node is protected and has been loaded with acquire semantics
+        self.curr().map(|curr| unsafe { curr.as_ref() })
+    }
+
+    /// Consumes the cursor and returns its guard, leaving the current node
+    /// protected in the [`CURR`][LinkCursor::CURR] slot.
+    #[inline]
+    pub fn into_guards(self) -> G {
+        self.guards
+    }
+
+    /// Resets the cursor to the first node reachable from the head.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.restart();
+        self.settle();
+    }
+
+    /// Advances the cursor to the next node.
+    #[inline]
+    pub fn step(&mut self) -> Step {
+        let curr = match MarkedNonNull::new(self.curr) {
+            Ok(curr) => curr,
+            Err(_) => return Step::End,
+        };
+
+        // safety: the current node is protected
+        self.prev = NonNull::from(unsafe { &*curr.decompose_ptr() }.link());
+        self.curr = self.next;
+        self.guards.shift();
+
+        if self.settle() {
+            Step::Next
+        } else {
+            Step::Restart
+        }
+    }
+
+    /// Advances the cursor until it is positioned at the first node for which
+    /// `pred` returns `true` and returns `true` or, if there is no such node,
+    /// at the end and returns `false`.
+    ///
+    /// The search starts at the current position, but may be restarted from
+    /// the first node if the traversal can not be validated.
+    #[inline]
+    pub fn find(&mut self, mut pred: impl FnMut(&T) -> bool) -> bool {
+        loop {
+            match self.curr_ref() {
+                None => return false,
+                Some(node) if pred(node) => return true,
+                Some(_) => {}
+            };
+
+            self.step();
+        }
+    }
+
+    /// Links the `node` in between the predecessor and the current node, so
+    /// that it directly precedes the current node.
+    ///
+    /// The cursor remains positioned at the current node.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the predecessor no longer links to the current node, in which
+    /// case the `node` is returned and the cursor is reset.
+    #[inline]
+    pub fn insert(&mut self, node: Owned<T, S::Reclaim, N>) -> Result<(), Owned<T, S::Reclaim, N>> {
+        let (curr, tag) = (self.curr.clear_tag(), self.curr.decompose_tag());
+        node.link().store(unsafe { Protected::from_marked_ptr(curr) }, Ordering::Relaxed);
+
+        let expected = unsafe { Protected::from_marked_ptr(self.curr) };
+        match self.prev().compare_exchange(expected, Owned::set_tag(node, tag), Self::REL_RLX) {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                self.reset();
+                Err(Owned::clear_tag(err.input))
+            }
+        }
+    }
+
+    /// Logically deletes the current node by marking its link and attempts to
+    /// unlink and retire it.
+    ///
+    /// Returns `false` if the cursor is positioned at the end or the node has
+    /// been concurrently deleted by another thread.
+    /// If the node can not be unlinked immediately, it will be unlinked by a
+    /// subsequent traversal.
+    /// Afterwards, the cursor is positioned at the node that follows the
+    /// predecessor.
+    #[inline]
+    pub fn delete(&mut self) -> bool {
+        let mark = tag::into_raw::<T::Mark, N>(T::DELETED);
+        let curr = match MarkedNonNull::new(self.curr) {
+            Ok(curr) => curr,
+            Err(_) => return false,
+        };
+
+        // safety: the current node is protected
+        let link = unsafe { &*curr.decompose_ptr() }.link();
+        let next = link.fetch_or_tag(mark, Self::ACQ).into_marked_ptr();
+        let deleted = !Self::is_marked(next);
+        if deleted {
+            self.unlink(curr, next);
+        }
+
+        // the cursor must move on even if the node had already been deleted,
+        // since it would otherwise remain positioned at a deleted node
+        // safety: the link is either the head or part of the protected predecessor
+        let prev = unsafe { &*self.prev.as_ptr() };
+        self.curr = self.guards.protect_slot(Self::CURR, prev, Self::ACQ).into_marked_ptr();
+        self.settle();
+
+        deleted
+    }
+
+    /// Positions the cursor at the first node reachable from the head.
+    #[inline]
+    fn restart(&mut self) {
+        let head = self.head;
+        self.prev = NonNull::from(head);
+        self.curr = self.guards.protect_slot(Self::CURR, head, Self::ACQ).into_marked_ptr();
+    }
+
+    /// Protects and validates the successor of the current node, helping to
+    /// unlink any logically deleted nodes on the way.
+    ///
+    /// Returns `false` if the cursor had to be restarted.
+    #[inline]
+    fn settle(&mut self) -> bool {
+        let mut restarted = false;
+        loop {
+            // the predecessor has been logically deleted
+            if Self::is_marked(self.curr) {
+                restarted = true;
+                self.restart();
+                continue;
+            }
+
+            let curr = match MarkedNonNull::new(self.curr) {
+                Ok(curr) => curr,
+                Err(_) => {
+                    self.next = MarkedPtr::null();
+                    return !restarted;
+                }
+            };
+
+            // safety: the current node is protected
+            let link = unsafe { &*curr.decompose_ptr() }.link();
+            let next = self.guards.protect_slot(Self::NEXT, link, Self::ACQ).into_marked_ptr();
+
+            // the current node is no longer linked to its predecessor
+            if self.prev().load_raw(Self::ACQ) != self.curr {
+                restarted = true;
+                self.restart();
+                continue;
+            }
+
+            if Self::is_marked(next) {
+                if !self.unlink(curr, next) {
+                    restarted = true;
+                    self.restart();
+                    continue;
+                }
+
+                // the (protected) successor becomes the current node
+                self.curr = next.set_tag(self.curr.decompose_tag());
+                self.guards.swap_slots(Self::CURR, Self::NEXT);
+                continue;
+            }
+
+            self.next = next;
+            return !restarted;
+        }
+    }
+
+    /// Returns `true` if `ptr` is tagged with the [`DELETED`][Linked::DELETED]
+    /// tag, i.e., if it has been loaded from the link of a logically deleted
+    /// node.
+    #[inline]
+    fn is_marked(ptr: MarkedPtr<T, N>) -> bool {
+        tag::from_raw::<T::Mark, N>(ptr.decompose_tag()) == T::DELETED
+    }
+
+    /// Attempts to unlink the (logically deleted) node `curr` with the marked
+    /// successor `next` from its predecessor and retires it on success.
+    #[inline]
+    fn unlink(&self, curr: MarkedNonNull<T, N>, next: MarkedPtr<T, N>) -> bool {
+        let expected = unsafe { Shared::from_marked_non_null(curr) };
+        let succ = unsafe { Protected::from_marked_ptr(next.set_tag(curr.decompose_tag())) };
+        match self.prev().compare_exchange(expected, succ, Self::REL_RLX) {
+            Ok(unlinked) => {
+                // safety: nodes are only linked from one link and hence can not be reached by any
+                // other thread after being unlinked
+                unsafe { self.thread_state.retire_record(unlinked.into_retired()) };
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<T, S: ReclaimThreadState<T>, G, const N: usize> fmt::Debug for LinkCursor<'_, T, S, G, N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LinkCursor").field("curr", &self.curr).field("next", &self.next).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinkCursor, Linked};
+    use crate::guards::GuardArray;
+    use crate::leak::{Guard, Leaking};
+    use crate::{Atomic, Owned};
+
+    struct Node {
+        elem: i32,
+        next: Atomic<Node, Leaking, 1>,
+    }
+
+    impl Linked<Leaking, 1> for Node {
+        type Mark = bool;
+        const DELETED: bool = true;
+
+        fn link(&self) -> &Atomic<Self, Leaking, 1> {
+            &self.next
+        }
+    }
+
+    type Cursor<'a> = LinkCursor<'a, Node, Leaking, GuardArray<Guard, 3>, 1>;
+
+    fn cursor<'a>(head: &'a Atomic<Node, Leaking, 1>, leaking: &'a Leaking) -> Cursor<'a> {
+        unsafe { LinkCursor::new(head, GuardArray::from_fn(|_| Guard), leaking) }
+    }
+
+    #[test]
+    fn delete_deleted() {
+        let second = Owned::new(Node { elem: 2, next: Atomic::null() });
+        let head = Atomic::new(Owned::new(Node { elem: 1, next: Atomic::new(second) }));
+        let leaking = Leaking;
+
+        let (mut a, mut b) = (cursor(&head, &leaking), cursor(&head, &leaking));
+        assert_eq!(a.curr_ref().map(|node| node.elem), Some(1));
+        assert_eq!(b.curr_ref().map(|node| node.elem), Some(1));
+
+        assert!(a.delete());
+        assert_eq!(a.curr_ref().map(|node| node.elem), Some(2));
+
+        // the node has already been deleted through the other cursor, which
+        // must nonetheless move past it
+        assert!(!b.delete());
+        assert_eq!(b.curr_ref().map(|node| node.elem), Some(2));
+    }
+}
@@ -1,18 +1,92 @@
-use core::any::Any;
+use core::any::{Any, TypeId};
+use core::marker::PhantomData;
 use core::mem;
 use core::ptr;
 
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
+use crate::record::Record;
+use crate::retired::{LinkedHeader, Retired, RetiredLink};
 use crate::traits::ReclaimBase;
 
-type RetiredRecord<R, T> = crate::record::Record<<R as ReclaimBase>::Header, T>;
+type RetiredRecord<R, T> = Record<<R as ReclaimBase>::Header, T>;
 
 /********** macros ********************************************************************************/
 
+/// Implements [`ReclaimBase`] and [`Reclaim`][crate::Reclaim] for a type
+/// erased reclamation mechanism `$reclaim` with the (user) header `$header`.
+///
+/// The default variant uses a [`DynHeader`][crate::erased::DynHeader] and
+/// requires all records to be `'static`, whereas the `compact` variant uses a
+/// [`CompactHeader`][crate::erased::CompactHeader].
+/// If the mechanism is declared with a lifetime parameter (e.g.,
+/// `impl_erased_reclaim!(compact Scoped<'a>, ())`), it can reclaim records of
+/// any type `T: 'a`, otherwise all records are likewise required to be
+/// `'static`:
+///
+/// ```compile_fail
+/// use std::sync::atomic::AtomicUsize;
+///
+/// use conquer_reclaim::{impl_erased_reclaim, Reclaim};
+///
+/// struct DropCounting<'a>(&'a AtomicUsize);
+/// struct Compact;
+///
+/// impl_erased_reclaim!(compact Compact, ());
+///
+/// fn retire<T, R: Reclaim<T>>(_: &T) {}
+///
+/// let count = AtomicUsize::new(0);
+/// // `count` does not live long enough
+/// retire::<_, Compact>(&DropCounting(&count));
+/// ```
 #[macro_export]
 macro_rules! impl_erased_reclaim {
+    (@compact [$($lt:lifetime)?] $bound:lifetime, $reclaim:ty, $header:ty) => {
+        unsafe impl<$($lt)?> $crate::ReclaimBase for $reclaim {
+            type Header = $crate::erased::CompactHeader<$header>;
+            type Retired = $crate::erased::DynErased;
+
+            #[inline]
+            unsafe fn reclaim(retired: *mut $crate::erased::DynErased) {
+                <Self as $crate::erased::CompactReclaim<$header>>::compact_reclaim(retired);
+            }
+
+            #[inline(always)]
+            unsafe fn as_data_ptr(retired: *mut $crate::erased::DynErased) -> *mut () {
+                <Self as $crate::erased::CompactReclaim<$header>>::as_data_ptr(retired)
+            }
+
+            #[inline(always)]
+            unsafe fn as_header_ptr(
+                retired: *mut $crate::erased::DynErased,
+            ) -> *mut $crate::erased::CompactHeader<$header> {
+                <Self as $crate::erased::CompactReclaim<$header>>::as_header_ptr(retired)
+            }
+
+            #[inline]
+            unsafe fn record_size(retired: *mut $crate::erased::DynErased) -> usize {
+                <Self as $crate::erased::CompactReclaim<$header>>::compact_record_size(retired)
+            }
+        }
+
+        unsafe impl<$($lt,)? T: $bound> $crate::Reclaim<T> for $reclaim {
+            #[inline]
+            unsafe fn retire(ptr: *mut T) -> *mut $crate::erased::DynErased {
+                <Self as $crate::erased::CompactReclaim<$header>>::compact_retire(ptr)
+            }
+        }
+    };
+    (compact $reclaim:ident<$lt:lifetime>, $header:ty) => {
+        $crate::impl_erased_reclaim!(@compact [$lt] $lt, $reclaim<$lt>, $header);
+    };
+    (compact $reclaim:ty, $header:ty) => {
+        $crate::impl_erased_reclaim!(@compact [] 'static, $reclaim, $header);
+    };
     ($reclaim:ty, $header:ty) => {
         unsafe impl $crate::ReclaimBase for $reclaim {
-            type Header = $header;
+            type Header = $crate::erased::DynHeader<$header>;
             type Retired = $crate::erased::DynErased;
 
             #[inline]
@@ -21,16 +95,21 @@ macro_rules! impl_erased_reclaim {
             }
 
             #[inline(always)]
-            unsafe fn as_data_ptr(
-                retired: *mut $crate::erased::DynErased,
-            ) -> *mut dyn core::any::Any {
-                <Self as $crate::DynReclaim<$header>>::as_data_ptr(retired);
+            unsafe fn as_data_ptr(retired: *mut $crate::erased::DynErased) -> *mut () {
+                <Self as $crate::erased::DynReclaim<$header>>::as_data_ptr(retired) as *mut ()
             }
 
             #[inline(always)]
-            unsafe fn as_header_ptr(retired: *mut $crate::erased::DynErased) -> *mut $header {
+            unsafe fn as_header_ptr(
+                retired: *mut $crate::erased::DynErased,
+            ) -> *mut $crate::erased::DynHeader<$header> {
                 <Self as $crate::erased::DynReclaim<$header>>::as_header_ptr(retired)
             }
+
+            #[inline]
+            unsafe fn record_size(retired: *mut $crate::erased::DynErased) -> usize {
+                <Self as $crate::erased::DynReclaim<$header>>::dyn_record_size(retired)
+            }
         }
 
         unsafe impl<T: 'static> $crate::Reclaim<T> for $reclaim {
@@ -54,9 +133,24 @@ macro_rules! impl_erased_reclaim {
 ///
 /// Use the [`impl_erased_reclaim`] to automatically implement these two traits
 /// as well as [`Retire`] for a reclamation mechanism.
+///
+/// # Safety
+///
+/// The provided methods rely on every record of the implementing mechanism
+/// being allocated as a `Record` with a [`DynHeader`], which is initialized
+/// by [`dyn_retire`][DynReclaim::dyn_retire] before any of the other methods
+/// are called for it.
+/// Overriding any of the provided methods is not supported.
 pub unsafe trait DynReclaim<H: 'static>:
     ReclaimBase<Header = DynHeader<H>, Retired = DynErased>
 {
+    /// Initializes the header of the record containing the value pointed to
+    /// by `ptr` and returns the type-erased retired pointer to it.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point at the value of a live record allocated for `Self`,
+    /// which must not have been retired before.
     #[inline]
     unsafe fn dyn_retire<T: 'static>(ptr: *mut T) -> *mut DynErased {
         let record = RetiredRecord::<Self, T>::header_from_data(ptr);
@@ -65,23 +159,57 @@ pub unsafe trait DynReclaim<H: 'static>:
         record as *mut _
     }
 
+    /// Drops and de-allocates the `retired` record.
+    ///
+    /// # Safety
+    ///
+    /// `retired` must have been returned by
+    /// [`dyn_retire`][DynReclaim::dyn_retire] and the record must no longer be
+    /// accessed by any thread, nor be reclaimed more than once.
     #[inline]
     unsafe fn dyn_reclaim(retired: *mut DynErased) {
         let header = retired as *mut DynHeader<H>;
         let record = RetiredRecord::<Self, dyn Any>::record_from_data((*header).data_ptr);
-        mem::drop(Box::from(record));
+        mem::drop(Box::from_raw(record));
     }
 
+    /// Returns a pointer to the `retired` record's value.
+    ///
+    /// # Safety
+    ///
+    /// `retired` must have been returned by
+    /// [`dyn_retire`][DynReclaim::dyn_retire] and the record must not yet have
+    /// been reclaimed, otherwise the returned pointer is invalid.
     #[inline(always)]
     unsafe fn as_data_ptr(retired: *mut DynErased) -> *mut dyn Any {
         let header = retired as *mut DynHeader<H>;
         (*header).data_ptr
     }
 
+    /// Returns a pointer to the `retired` record's header.
+    ///
+    /// # Safety
+    ///
+    /// `retired` must have been returned by
+    /// [`dyn_retire`][DynReclaim::dyn_retire], otherwise the returned pointer
+    /// is invalid.
     #[inline(always)]
     unsafe fn as_header_ptr(retired: *mut DynErased) -> *mut Self::Header {
         retired as *mut _
     }
+
+    /// Returns the size in bytes of the `retired` record, including its header.
+    ///
+    /// # Safety
+    ///
+    /// `retired` must have been returned by
+    /// [`dyn_retire`][DynReclaim::dyn_retire] and the record must not yet have
+    /// been reclaimed.
+    #[inline]
+    unsafe fn dyn_record_size(retired: *mut DynErased) -> usize {
+        let header = retired as *mut DynHeader<H>;
+        mem::size_of_val(&*RetiredRecord::<Self, dyn Any>::record_from_data((*header).data_ptr))
+    }
 }
 
 /********** blanket impl **************************************************************************/
@@ -92,6 +220,127 @@ unsafe impl<H: 'static, R> DynReclaim<H> for R where
 {
 }
 
+// *************************************************************************************************
+// CompactReclaim (trait)
+// *************************************************************************************************
+
+/// An extension trait for [`ReclaimBase`] like [`DynReclaim`], but for
+/// reclamation mechanisms using the more space efficient [`CompactHeader`].
+///
+/// This trait is not meant to be implemented manually, since there exists a
+/// blanket implementation for every type implementing [`ReclaimBase`] using
+/// the appropriate associated types.
+///
+/// Use the `compact` variant of [`impl_erased_reclaim`] to automatically
+/// implement this trait for a reclamation mechanism.
+///
+/// # Safety
+///
+/// The provided methods rely on every record of the implementing mechanism
+/// being allocated as a `Record` with a [`CompactHeader`], whose vtable is
+/// initialized by [`compact_retire`][CompactReclaim::compact_retire] before
+/// any of the other methods are called for it.
+/// Overriding any of the provided methods is not supported.
+pub unsafe trait CompactReclaim<H: 'static>:
+    ReclaimBase<Header = CompactHeader<H>, Retired = DynErased>
+{
+    /// Stores the vtable for records of type `T` in the header of the record
+    /// containing the value pointed to by `ptr` and returns the type-erased
+    /// retired pointer to it.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point at the value of a live record allocated for `Self`,
+    /// which must not have been retired before.
+    #[inline]
+    unsafe fn compact_retire<T>(ptr: *mut T) -> *mut DynErased {
+        let record = RetiredRecord::<Self, T>::header_from_data(ptr);
+        (*record).vtable = Some(&VTable::<H, T>::VTABLE);
+
+        record as *mut _
+    }
+
+    /// Drops and de-allocates the `retired` record.
+    ///
+    /// # Safety
+    ///
+    /// `retired` must have been returned by
+    /// [`compact_retire`][CompactReclaim::compact_retire] and the record must
+    /// no longer be accessed by any thread, nor be reclaimed more than once.
+    ///
+    /// # Panics
+    ///
+    /// Panics (through `unreachable!`), if the record has not been retired, in
+    /// which case its vtable is still uninitialized (see
+    /// [`vtable`][CompactReclaim::vtable]).
+    #[inline]
+    unsafe fn compact_reclaim(retired: *mut DynErased) {
+        (Self::vtable(retired).drop_record)(retired as *mut ());
+    }
+
+    /// Returns a pointer to the `retired` record's value.
+    ///
+    /// # Safety
+    ///
+    /// `retired` must have been returned by
+    /// [`compact_retire`][CompactReclaim::compact_retire] and the record must
+    /// not yet have been reclaimed (see [`vtable`][CompactReclaim::vtable]).
+    #[inline(always)]
+    unsafe fn as_data_ptr(retired: *mut DynErased) -> *mut () {
+        (retired as *mut u8).add(Self::vtable(retired).data_offset).cast()
+    }
+
+    /// Returns a pointer to the `retired` record's header.
+    ///
+    /// # Safety
+    ///
+    /// `retired` must have been returned by
+    /// [`compact_retire`][CompactReclaim::compact_retire], otherwise the
+    /// returned pointer is invalid.
+    #[inline(always)]
+    unsafe fn as_header_ptr(retired: *mut DynErased) -> *mut Self::Header {
+        retired as *mut _
+    }
+
+    /// Returns the size in bytes of the `retired` record, including its header.
+    ///
+    /// # Safety
+    ///
+    /// `retired` must have been returned by
+    /// [`compact_retire`][CompactReclaim::compact_retire] and the record must
+    /// not yet have been reclaimed (see [`vtable`][CompactReclaim::vtable]).
+    #[inline]
+    unsafe fn compact_record_size(retired: *mut DynErased) -> usize {
+        Self::vtable(retired).record_size
+    }
+
+    /// Returns the vtable stored in the `retired` record's header.
+    ///
+    /// # Safety
+    ///
+    /// `retired` must point at the header of a live record allocated for
+    /// `Self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics (through `unreachable!`), if the record has not been retired
+    /// through [`compact_retire`][CompactReclaim::compact_retire], since only
+    /// then its vtable is initialized.
+    #[inline(always)]
+    unsafe fn vtable(retired: *mut DynErased) -> &'static RecordVTable {
+        let header = retired as *mut CompactHeader<H>;
+        (*header).vtable.unwrap_or_else(|| unreachable!("record has not been retired"))
+    }
+}
+
+/********** blanket impl **************************************************************************/
+
+// CompactReclaim is implemented automatically
+unsafe impl<H: 'static, R> CompactReclaim<H> for R where
+    R: ReclaimBase<Header = CompactHeader<H>, Retired = DynErased>
+{
+}
+
 // *************************************************************************************************
 // DynErased
 // *************************************************************************************************
@@ -123,7 +372,8 @@ pub struct DynErased(());
 pub struct DynHeader<H> {
     // NOTE: it would be sufficient and more space efficient to simply store the correct vtable
     // pointer and only construct the corresponding fat pointer when the record is reclaimed, but
-    // the internal layout of fat pointers is unlikely to be stabilized soon, if ever
+    // the internal layout of fat pointers is unlikely to be stabilized soon, if ever (see
+    // `CompactHeader` for an alternative, which does not support downcasting, however)
     pub(crate) data_ptr: *mut dyn Any,
     pub header: H,
 }
@@ -144,3 +394,236 @@ impl<H> DynHeader<H> {
         Self { data_ptr: null as *mut dyn Any, header }
     }
 }
+
+/********** impl Default **************************************************************************/
+
+impl<H: Default> Default for DynHeader<H> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+/********** impl LinkedHeader *********************************************************************/
+
+unsafe impl<H: LinkedHeader> LinkedHeader for DynHeader<H> {
+    #[inline]
+    fn retired_link(&self) -> &RetiredLink {
+        self.header.retired_link()
+    }
+}
+
+// *************************************************************************************************
+// CompactHeader
+// *************************************************************************************************
+
+/// A header wrapper for a type erased record, which is more space efficient
+/// than [`DynHeader`].
+///
+/// Instead of a (two words wide) `*mut dyn Any` pointer, the wrapper stores
+/// only a single (thin) reference to a static [`RecordVTable`], which is
+/// generated for each record type when a record is retired.
+/// Since no `dyn Any` is involved, records of this kind do not support
+/// downcasting, but are also not required to be `'static`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct CompactHeader<H> {
+    pub(crate) vtable: Option<&'static RecordVTable>,
+    pub header: H,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<H> CompactHeader<H> {
+    /// Wraps the given `header` in a [`CompactHeader`].
+    ///
+    /// The resulting header wrapper is **not** yet initialized fully and can
+    /// thus not be reclaimed as-is!
+    /// It is necessary to always call
+    /// [`compact_retire`][CompactReclaim::compact_retire] on a pointer to the
+    /// associated record's data, before calling
+    /// [`compact_reclaim`][CompactReclaim::compact_reclaim].
+    #[inline]
+    pub const fn new(header: H) -> Self {
+        Self { vtable: None, header }
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<H: Default> Default for CompactHeader<H> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+/********** impl LinkedHeader *********************************************************************/
+
+unsafe impl<H: LinkedHeader> LinkedHeader for CompactHeader<H> {
+    #[inline]
+    fn retired_link(&self) -> &RetiredLink {
+        self.header.retired_link()
+    }
+}
+
+// *************************************************************************************************
+// RecordVTable
+// *************************************************************************************************
+
+/// The (static) information required for reclaiming a type erased record
+/// with a [`CompactHeader`].
+#[derive(Debug)]
+pub struct RecordVTable {
+    drop_record: unsafe fn(*mut ()),
+    data_offset: usize,
+    record_size: usize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl RecordVTable {
+    /// Returns the offset in bytes from the start of the record to its data.
+    #[inline]
+    pub fn data_offset(&self) -> usize {
+        self.data_offset
+    }
+
+    /// Returns the size in bytes of the entire record, including its header.
+    #[inline]
+    pub fn record_size(&self) -> usize {
+        self.record_size
+    }
+}
+
+/// A helper type for generating a [`RecordVTable`] for each header and record
+/// type combination.
+struct VTable<H, T>(PhantomData<(H, T)>);
+
+impl<H, T> VTable<H, T> {
+    const VTABLE: RecordVTable = RecordVTable {
+        drop_record: drop_record::<H, T>,
+        data_offset: Record::<CompactHeader<H>, T>::data_offset(mem::align_of::<T>()),
+        record_size: mem::size_of::<Record<CompactHeader<H>, T>>(),
+    };
+}
+
+/// Drops and de-allocates the record of type `T` starting at `record`.
+unsafe fn drop_record<H, T>(record: *mut ()) {
+    mem::drop(Box::from_raw(record as *mut Record<CompactHeader<H>, T>));
+}
+
+// *************************************************************************************************
+// Retired (type-erased)
+// *************************************************************************************************
+
+/********** impl inherent *************************************************************************/
+
+impl<H: 'static, R> Retired<R>
+where
+    R: ReclaimBase<Header = DynHeader<H>, Retired = DynErased>,
+{
+    /// Returns the [`TypeId`] of the retired record's value.
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.as_dyn_ref().type_id()
+    }
+
+    /// Returns a reference to the retired record's value, if it is of type
+    /// `T`.
+    #[inline]
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.as_dyn_ref().downcast_ref()
+    }
+
+    /// Returns the size in bytes of the retired record's value, excluding its
+    /// header.
+    #[inline]
+    pub fn size_of_val(&self) -> usize {
+        mem::size_of_val(self.as_dyn_ref())
+    }
+
+    #[inline]
+    fn as_dyn_ref(&self) -> &dyn Any {
+        // safety: the record is live until it is reclaimed and its header has been initialized when
+        // it was retired
+        unsafe { &*(*self.header_ptr()).data_ptr }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+    use std::marker::PhantomData;
+    use std::mem;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use conquer_pointer::MarkedPtr;
+
+    use super::CompactHeader;
+    use crate::traits::Reclaim;
+    use crate::{Owned, Retired, Unlinked};
+
+    struct DropCounting<'a>(&'a AtomicUsize, [u64; 3]);
+
+    impl Drop for DropCounting<'_> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    struct Erased;
+    struct Compact;
+    struct Scoped<'a>(PhantomData<&'a ()>);
+
+    impl_erased_reclaim!(Erased, ());
+    impl_erased_reclaim!(compact Compact, ());
+    impl_erased_reclaim!(compact Scoped<'a>, ());
+
+    fn retired<T, R: Reclaim<T>>(value: T) -> Retired<R>
+    where
+        R::Header: Default,
+    {
+        let owned = Owned::<_, R, 0>::new(value);
+        let ptr: MarkedPtr<_, 0> = Owned::into_marked_ptr(owned);
+        unsafe { Unlinked::<_, R, 0>::from_marked_ptr(ptr) }.into_retired()
+    }
+
+    #[test]
+    fn downcast_retired() {
+        static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+        let mut retired = retired::<_, Erased>(DropCounting(&DROP_COUNT, [1, 2, 3]));
+
+        assert_eq!(retired.type_id(), TypeId::of::<DropCounting>());
+        assert!(retired.downcast_ref::<u64>().is_none());
+        assert_eq!(retired.downcast_ref::<DropCounting>().unwrap().1, [1, 2, 3]);
+        assert_eq!(retired.size_of_val(), 32);
+        assert!(retired.record_size() > retired.size_of_val());
+
+        unsafe { retired.reclaim() };
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn compact_retired() {
+        static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+        assert_eq!(mem::size_of::<CompactHeader<()>>(), mem::size_of::<usize>());
+
+        let mut retired = retired::<_, Compact>(DropCounting(&DROP_COUNT, [1, 2, 3]));
+        assert_eq!(unsafe { (*retired.as_ptr().cast::<DropCounting>()).1 }, [1, 2, 3]);
+        assert_eq!(retired.record_size(), 40);
+
+        unsafe { retired.reclaim() };
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn scoped_retired() {
+        let drop_count = AtomicUsize::new(0);
+        let mut retired = retired::<_, Scoped>(DropCounting(&drop_count, [1, 2, 3]));
+        assert_eq!(retired.record_size(), 40);
+
+        unsafe { retired.reclaim() };
+        assert_eq!(drop_count.load(Ordering::Relaxed), 1);
+    }
+}
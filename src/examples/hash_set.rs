@@ -1,12 +1,10 @@
 use core::borrow::Borrow;
 use core::cmp::{
     Ord,
-    Ordering::{Equal, Greater},
+    Ordering::{Equal, Less},
 };
 use core::hash::{BuildHasher, Hash, Hasher};
 use core::mem::ManuallyDrop;
-use core::ops::Deref;
-use core::sync::atomic::Ordering;
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "std")] {
@@ -16,16 +14,26 @@ cfg_if::cfg_if! {
     }
 }
 
-use crate::{ProtectExt, ReclaimRef, ReclaimThreadState};
+use crate::array::AtomicSlice;
+use crate::cursor::{LinkCursor, Linked};
+use crate::guards::GuardArray;
+use crate::tag::Tag;
+use crate::{ReclaimRef, ReclaimThreadState};
 
 type Atomic<T, R> = crate::Atomic<T, R, 1>;
 type Owned<T, R> = crate::Owned<T, R, 1>;
 
-type FusedProtected<T, G> = crate::fused::FusedProtected<T, G, 1>;
 type FusedShared<T, G> = crate::fused::FusedShared<T, G, 1>;
-type FusedSharedRef<'g, T, G> = crate::fused::FusedSharedRef<'g, T, G, 1>;
+type FusedMapped<'a, T, G> = crate::fused::FusedMapped<'a, T, G>;
 
 type AssocGuard<T, R> = <<R as ReclaimRef<T>>::ThreadState as ReclaimThreadState<T>>::Guard;
+type Cursor<'a, T, R> = LinkCursor<
+    'a,
+    Node<T, R>,
+    <R as ReclaimRef<Node<T, R>>>::ThreadState,
+    GuardArray<AssocGuard<Node<T, R>, R>, 3>,
+    1,
+>;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // ArcHashSet
@@ -104,7 +112,7 @@ where
     }
 
     #[inline]
-    pub fn get<Q>(&self, value: &Q) -> Option<SharedRef<T, R>>
+    pub fn get<Q>(&self, value: &Q) -> Option<FusedMapped<'a, T, AssocGuard<Node<T, R>, R>>>
     where
         T: Borrow<Q>,
         Q: Hash + Ord,
@@ -118,7 +126,7 @@ where
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 pub struct HashSet<T, R: ReclaimRef<Node<T, R>>, S> {
-    buckets: Box<[OrderedSet<T, R>]>,
+    buckets: AtomicSlice<Node<T, R>, R::Reclaim, 1>,
     reclaimer: R,
     hash_builder: S,
 }
@@ -134,20 +142,14 @@ where
     #[inline]
     pub fn with(hash_builder: S, buckets: usize, reclaimer: R) -> Self {
         assert!(buckets > 0, "hash set needs to contain at least one bucket");
-        Self { buckets: (0..buckets).map(|_| OrderedSet::new()).collect(), reclaimer, hash_builder }
+        Self { buckets: AtomicSlice::with_len(buckets), reclaimer, hash_builder }
     }
 
     #[inline]
     pub unsafe fn insert(&self, elem: T, thread_state: &R::ThreadState) -> bool {
-        let mut prev = thread_state.build_guard();
-        let curr = thread_state.build_guard();
-        let next = thread_state.build_guard();
-
         let node = thread_state.alloc_owned(Node { elem, next: Atomic::null() });
-        let elem = &node.elem;
-        let set = &self.buckets[self.make_hash(elem)];
-
-        return set.insert_node(node, thread_state, &mut prev, curr, next);
+        let set = self.bucket(&node.elem);
+        set.insert_node(node, thread_state)
     }
 
     #[inline]
@@ -156,12 +158,8 @@ where
         T: Borrow<Q>,
         Q: Hash + Ord,
     {
-        let mut prev = thread_state.build_guard();
-        let curr = thread_state.build_guard();
-        let next = thread_state.build_guard();
-
-        let set = &self.buckets[self.make_hash(value)];
-        set.remove_node(value, thread_state, &mut prev, curr, next)
+        let set = self.bucket(value);
+        set.remove_node(value, thread_state)
     }
 
     #[inline]
@@ -170,297 +168,250 @@ where
         T: Borrow<Q>,
         Q: Hash + Ord,
     {
-        let mut prev = thread_state.build_guard();
-        let curr = thread_state.build_guard();
-        let next = thread_state.build_guard();
-
-        let set = &self.buckets[self.make_hash(value)];
-        match set.find(value, thread_state, &mut prev, curr, next) {
-            FindResult::Found { .. } => true,
-            _ => false,
-        }
+        let set = self.bucket(value);
+        OrderedSet::find(&mut set.cursor(thread_state), value)
     }
 
     #[inline]
-    pub unsafe fn get<Q>(&self, value: &Q, thread_state: &R::ThreadState) -> Option<SharedRef<T, R>>
+    pub unsafe fn get<'a, Q>(
+        &'a self,
+        value: &Q,
+        thread_state: &R::ThreadState,
+    ) -> Option<FusedMapped<'a, T, AssocGuard<Node<T, R>, R>>>
     where
         T: Borrow<Q>,
         Q: Hash + Ord,
     {
-        let mut prev = thread_state.build_guard();
-        let curr = thread_state.build_guard();
-        let next = thread_state.build_guard();
-
-        let set = &self.buckets[self.make_hash(value)];
-        match set.find(value, thread_state, &mut prev, curr, next) {
-            FindResult::Found { curr, .. } => Some(SharedRef { shared: curr }),
-            FindResult::Insert { .. } => None,
+        let set = self.bucket(value);
+        let mut cursor = set.cursor(thread_state);
+        if !OrderedSet::find(&mut cursor, value) {
+            return None;
         }
+
+        let shared = cursor.curr()?.inner;
+        let [_, guard, _] = cursor.into_guards().into_inner();
+        // safety: the mapped reference borrows the set, which frees all nodes when dropped
+        Some(FusedShared { guard, shared }.map(|node| &node.elem))
     }
 
     #[inline]
-    fn make_hash<Q>(&self, value: &Q) -> usize
+    fn bucket<Q>(&self, value: &Q) -> OrderedSet<T, R>
     where
         T: Borrow<Q>,
         Q: Hash + Ord,
     {
         let mut state = self.hash_builder.build_hasher();
         value.hash(&mut state);
-        (state.finish() % self.buckets.len() as u64) as usize
+        let idx = (state.finish() % self.buckets.len() as u64) as usize;
+        OrderedSet { head: &self.buckets[idx] }
+    }
+}
+
+/*********** impl Drop ****************************************************************************/
+
+impl<T, R: ReclaimRef<Node<T, R>>, S> Drop for HashSet<T, R, S> {
+    #[inline]
+    fn drop(&mut self) {
+        // safety: `&mut self` guarantees that no other thread can access the set, all retired
+        // nodes have been unlinked and are hence no longer reachable
+        for head in unsafe { self.buckets.take_all() } {
+            let mut curr = Some(head);
+            while let Some(mut node) = curr {
+                curr = unsafe { node.next.take() };
+            }
+        }
     }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
-// SharedRef
+// Node
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-pub struct SharedRef<T, R: ReclaimRef<Node<T, R>>> {
-    shared: FusedShared<Node<T, R>, AssocGuard<Node<T, R>, R>>,
+pub struct Node<T, R: ReclaimRef<Self>> {
+    elem: T,
+    next: Atomic<Self, R::Reclaim>,
 }
 
-/********** impl Deref ****************************************************************************/
+/********** impl Linked ***************************************************************************/
 
-impl<T, R: ReclaimRef<Node<T, R>>> Deref for SharedRef<T, R> {
-    type Target = T;
+impl<T, R: ReclaimRef<Self>> Linked<R::Reclaim, 1> for Node<T, R> {
+    type Mark = Marked;
+    const DELETED: Marked = Marked::Deleted;
 
     #[inline]
-    fn deref(&self) -> &Self::Target {
-        unsafe { &self.shared.as_shared().as_ref().elem }
+    fn link(&self) -> &Atomic<Self, R::Reclaim> {
+        &self.next
     }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
-// Node
+// Marked
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-pub struct Node<T, R: ReclaimRef<Self>> {
-    elem: T,
-    next: Atomic<Self, R::Reclaim>,
+/// The tag of a node's `next` pointer, which marks the node as logically
+/// deleted.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Marked {
+    Unmarked,
+    Deleted,
+}
+
+/********** impl Tag ******************************************************************************/
+
+impl Tag for Marked {
+    const BITS: usize = 1;
+
+    #[inline]
+    fn into_usize(self) -> usize {
+        self as usize
+    }
+
+    #[inline]
+    fn from_usize(tag: usize) -> Self {
+        match tag {
+            0 => Marked::Unmarked,
+            _ => Marked::Deleted,
+        }
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 // OrderedSet
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-struct OrderedSet<T, R: ReclaimRef<Node<T, R>>> {
-    head: Atomic<Node<T, R>, R::Reclaim>,
+struct OrderedSet<'a, T, R: ReclaimRef<Node<T, R>>> {
+    head: &'a Atomic<Node<T, R>, R::Reclaim>,
 }
 
 /********** impl inherent *************************************************************************/
 
-impl<T, R> OrderedSet<T, R>
+impl<'a, T, R> OrderedSet<'a, T, R>
 where
     T: Ord,
     R: ReclaimRef<Node<T, R>>,
 {
-    const DELETE_TAG: usize = 0x1;
-
-    const ACQ: Ordering = Ordering::Acquire;
-    const RLX: Ordering = Ordering::Relaxed;
-    const ACQ_RLX: (Ordering, Ordering) = (Ordering::Acquire, Ordering::Relaxed);
-    const REL_RLX: (Ordering, Ordering) = (Ordering::Release, Ordering::Relaxed);
-
-    #[inline]
-    fn new() -> Self {
-        Self { head: Atomic::null() }
-    }
-
     #[inline]
     unsafe fn insert_node(
         &self,
         mut node: Owned<Node<T, R>, R::Reclaim>,
         thread_state: &R::ThreadState,
-        prev_guard: &mut AssocGuard<Node<T, R>, R>,
-        mut curr_guard: AssocGuard<Node<T, R>, R>,
-        mut next_guard: AssocGuard<Node<T, R>, R>,
     ) -> bool {
+        let mut cursor = self.cursor(thread_state);
         loop {
-            let elem = &node.elem;
-            match self.find(elem, thread_state, prev_guard, curr_guard, next_guard) {
-                FindResult::Insert { prev, next, guard } => {
-                    node.next.store(next.as_protected(), Ordering::Relaxed);
-                    match prev.as_ref().compare_exchange(next.as_protected(), node, Self::REL_RLX) {
-                        Ok(_) => return true,
-                        Err(fail) => {
-                            node = fail.input;
-                            curr_guard = next.into_guard();
-                            next_guard = guard;
-                        }
-                    };
-                }
-                FindResult::Found { .. } => {
-                    return false;
-                }
+            if OrderedSet::find(&mut cursor, &node.elem) {
+                return false;
+            }
+
+            match cursor.insert(node) {
+                Ok(_) => return true,
+                Err(input) => node = input,
             }
         }
     }
 
     #[inline]
-    unsafe fn remove_node<Q>(
-        &self,
-        value: &Q,
-        thread_state: &R::ThreadState,
-        prev_guard: &mut AssocGuard<Node<T, R>, R>,
-        mut curr_guard: AssocGuard<Node<T, R>, R>,
-        mut next_guard: AssocGuard<Node<T, R>, R>,
-    ) -> bool
+    unsafe fn remove_node<Q>(&self, value: &Q, thread_state: &R::ThreadState) -> bool
     where
         T: Borrow<Q>,
         Q: Ord,
     {
+        let mut cursor = self.cursor(thread_state);
         loop {
-            match self.find(value, thread_state, prev_guard, curr_guard, next_guard) {
-                FindResult::Insert { .. } => return false,
-                FindResult::Found { prev, curr, next } => {
-                    let next_ref = &curr.as_shared().as_ref().next;
-                    let next_before = next.as_protected();
-                    let next_marked = next_before.set_tag(Self::DELETE_TAG);
-
-                    if next_ref.compare_exchange(next_before, next_marked, Self::ACQ_RLX).is_err() {
-                        curr_guard = curr.into_guard();
-                        next_guard = next.into_guard();
-                        continue;
-                    }
-
-                    let curr_ref = curr.as_shared();
-                    match prev.as_ref().compare_exchange(curr_ref, next_before, Self::REL_RLX) {
-                        Ok(unlinked) => thread_state.retire_record(unlinked.into_retired()),
-                        Err(_) => {
-                            curr_guard = curr.into_guard();
-                            next_guard = next.into_guard();
-                            let _ =
-                                self.find(value, thread_state, prev_guard, curr_guard, next_guard);
-                        }
-                    }
+            if !OrderedSet::find(&mut cursor, value) {
+                return false;
+            }
 
-                    return true;
-                }
+            // a failed deletion means the node has been concurrently removed
+            // and the cursor has moved past it, so the search is repeated
+            if cursor.delete() {
+                return true;
             }
         }
     }
 
-    unsafe fn find<'set, 'g, Q>(
-        &'set self,
-        value: &Q,
-        thread_state: &R::ThreadState,
-        prev_guard: &'g mut AssocGuard<Node<T, R>, R>,
-        mut curr_guard: AssocGuard<Node<T, R>, R>,
-        mut next_guard: AssocGuard<Node<T, R>, R>,
-    ) -> FindResult<'set, 'g, T, R>
+    #[inline]
+    unsafe fn cursor(&self, thread_state: &'a R::ThreadState) -> Cursor<'a, T, R> {
+        let guards = GuardArray::from_fn(|_| thread_state.build_guard());
+        LinkCursor::new(self.head, guards, thread_state)
+    }
+
+    /// Advances the `cursor` to the first node not less than `value` and
+    /// returns `true`, if that node is equal to `value`.
+    #[inline]
+    fn find<Q>(cursor: &mut Cursor<T, R>, value: &Q) -> bool
     where
         T: Borrow<Q>,
         Q: Ord,
     {
-        'retry: loop {
-            let mut prev = Previous::Set(&self.head);
-            loop {
-                match curr_guard.protect_fused(prev.as_ref(), Self::ACQ).into_fused_shared() {
-                    Ok(curr_fused) => {
-                        let (curr, tag) = curr_fused.as_shared().split_tag();
-                        if tag == Self::DELETE_TAG {
-                            curr_guard = curr_fused.into_guard();
-                            continue 'retry;
-                        }
-
-                        let next_ref = &curr.as_ref().next;
-
-                        let expected = next_ref.load_raw(Self::RLX);
-                        match next_guard.protect_fused_if_equal(next_ref, expected, Self::ACQ) {
-                            Err((next, _)) => {
-                                curr_guard = curr_fused.into_guard();
-                                next_guard = next;
-                                continue 'retry;
-                            }
-                            Ok(next_fused) => {
-                                if prev.as_ref().load_raw(Self::RLX) != curr.into_marked_ptr() {
-                                    curr_guard = curr_fused.into_guard();
-                                    next_guard = next_fused.into_guard();
-                                    continue 'retry;
-                                }
-
-                                let (next, tag) = next_fused.as_protected().split_tag();
-                                if tag == Self::DELETE_TAG {
-                                    match prev.as_ref().compare_exchange(curr, next, Self::REL_RLX)
-                                    {
-                                        Ok(unlinked) => {
-                                            thread_state.retire_record(unlinked.into_retired())
-                                        }
-                                        Err(_) => {
-                                            curr_guard = curr_fused.into_guard();
-                                            next_guard = next_fused.into_guard();
-                                            continue 'retry;
-                                        }
-                                    }
-                                } else {
-                                    match curr.as_ref().elem.borrow().cmp(value) {
-                                        Equal => {
-                                            return FindResult::Found {
-                                                prev,
-                                                curr: curr_fused,
-                                                next: next_fused,
-                                            }
-                                        }
-                                        Greater => {
-                                            return FindResult::Insert {
-                                                prev,
-                                                next: curr_fused.into_fused_protected(),
-                                                guard: next_fused.into_guard(),
-                                            }
-                                        }
-                                        _ => {}
-                                    };
-                                }
-
-                                // SAFETY: ..
-                                let prev_guard = &mut *(prev_guard as *mut _);
-                                let (prev_fused, free) = curr_fused.transfer_to_ref(prev_guard);
-                                curr_guard = free;
-                                next_guard = next_fused.into_guard();
-                                prev = Previous::Guarded(prev_fused);
-                            }
-                        }
-                    }
-                    Err((next, _)) => return FindResult::Insert { prev, next, guard: next_guard },
-                }
-            }
-        }
+        cursor.find(|node| node.elem.borrow().cmp(value) != Less)
+            && cursor.curr_ref().map_or(false, |node| node.elem.borrow().cmp(value) == Equal)
     }
 }
 
-////////////////////////////////////////////////////////////////////////////////////////////////////
-// FindResult
-////////////////////////////////////////////////////////////////////////////////////////////////////
-
-enum FindResult<'set, 'g, T, R: ReclaimRef<Node<T, R>>> {
-    Found {
-        prev: Previous<'set, 'g, T, R>,
-        curr: FusedShared<Node<T, R>, AssocGuard<Node<T, R>, R>>,
-        next: FusedProtected<Node<T, R>, AssocGuard<Node<T, R>, R>>,
-    },
-    Insert {
-        prev: Previous<'set, 'g, T, R>,
-        next: FusedProtected<Node<T, R>, AssocGuard<Node<T, R>, R>>,
-        guard: AssocGuard<Node<T, R>, R>,
-    },
-}
-
-////////////////////////////////////////////////////////////////////////////////////////////////////
-// Previous
-////////////////////////////////////////////////////////////////////////////////////////////////////
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::RandomState;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    use super::{HashSet, HastSetRef};
+    use crate::leak::Leaking;
+
+    const THREADS: usize = 4;
+    const KEYS: i32 = 10_000;
+
+    #[test]
+    fn get_elems() {
+        let set = HashSet::with(RandomState::new(), 2, Leaking);
+        let set_ref = HastSetRef::new(&set);
+        for key in 0..3 {
+            assert!(set_ref.insert(key));
+        }
 
-enum Previous<'set, 'g, T, R: ReclaimRef<Node<T, R>>> {
-    Set(&'set Atomic<Node<T, R>, R::Reclaim>),
-    Guarded(FusedSharedRef<'g, Node<T, R>, AssocGuard<Node<T, R>, R>>),
-}
+        let elem = set_ref.get(&1).unwrap();
+        assert!(set_ref.remove(&1));
+        // the removed element remains protected by the returned reference
+        assert_eq!(*elem, 1);
+        assert!(set_ref.get(&1).is_none());
+    }
 
-/********** impl inherent *************************************************************************/
+    #[test]
+    fn concurrent_remove_same_key() {
+        let set = Arc::new(HashSet::with(RandomState::new(), 1, Leaking));
+        let barrier = Arc::new(Barrier::new(THREADS + 1));
+        let removed = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let (set, barrier, removed) =
+                    (Arc::clone(&set), Arc::clone(&barrier), Arc::clone(&removed));
+                thread::spawn(move || {
+                    let set = HastSetRef::new(&set);
+                    for key in 0..KEYS {
+                        barrier.wait();
+                        if set.remove(&key) {
+                            removed.fetch_add(1, Ordering::Relaxed);
+                        }
+                        barrier.wait();
+                    }
+                })
+            })
+            .collect();
+
+        let set = HastSetRef::new(&set);
+        for key in 0..KEYS {
+            assert!(set.insert(key));
+            barrier.wait();
+            barrier.wait();
+            assert!(!set.contains(&key));
+        }
 
-impl<T, R: ReclaimRef<Node<T, R>>> Previous<'_, '_, T, R> {
-    #[inline]
-    unsafe fn as_ref(&self) -> &Atomic<Node<T, R>, R::Reclaim> {
-        match self {
-            Previous::Set(head) => *head,
-            Previous::Guarded(shared) => &shared.as_shared().as_ref().next,
+        for handle in handles {
+            handle.join().unwrap();
         }
+
+        // every key has been removed by exactly one thread
+        assert_eq!(removed.load(Ordering::Relaxed), KEYS as usize);
     }
 }
@@ -250,19 +250,22 @@ impl<T, R: ReclaimRef<Node<T, R>>> Stack<T, R> {
     #[inline]
     pub unsafe fn pop_unchecked(&self, thread_state: &R::ThreadState) -> Option<T> {
         let mut guard = thread_state.build_guard();
-        while let Maybe::Some(shared) = self.head.load(&mut guard, Acquire).shared() {
-            // safety: `next` can be safely used as store argument for the subsequent CAS, since it
-            // will only be actually stored if it succeeds, in which case the node could not have
-            // been popped and retired/reclaimed in between.
-            let next = shared.as_ref().next.load_unprotected(Relaxed).assume_storable();
-            if let Ok(unlinked) = self.head.compare_exchange_weak(shared, next, Self::RELEASE_CAS) {
-                let elem = unlinked.take(|node| &node.elem);
-                thread_state.retire_record(unlinked.into_retired());
-                return Some(elem);
-            }
-        }
-
-        None
+        let popped =
+            self.head.update(&mut guard, thread_state, Acquire, Self::RELEASE_CAS, |head, _| {
+                match head.shared() {
+                    // safety: `next` can be safely used as store argument for the subsequent
+                    // CAS, since it will only be actually stored if it succeeds, in which case
+                    // the node could not have been popped and retired/reclaimed in between.
+                    Maybe::Some(shared) => {
+                        Some(shared.as_ref().next.load_unprotected(Relaxed).assume_storable())
+                    }
+                    Maybe::Null(_) => None,
+                }
+            });
+
+        // safety: the popped node remains protected by `guard` and only the thread that popped
+        // it can take its element.
+        popped.ok().map(|popped| ptr::read(&*popped.deref().elem))
     }
 }
 
@@ -3,7 +3,10 @@
 
 use core::convert::TryInto;
 use core::fmt;
+use core::marker::PhantomData;
 use core::mem;
+use core::ops::Deref;
+use core::ptr::NonNull;
 
 use conquer_pointer::{MarkedNonNull, MarkedPtr, Null};
 
@@ -56,6 +59,14 @@ impl<T, G: Protect<T>, const N: usize> FusedProtected<T, G, N> {
     pub fn into_guard(self) -> G {
         self.guard
     }
+
+    /// Consumes `self` and returns the contained guard instance after
+    /// [releasing][Protect::release] its currently protected value.
+    #[inline]
+    pub fn release(mut self) -> G {
+        self.guard.release();
+        self.guard
+    }
 }
 
 /********** impl Debug ****************************************************************************/
@@ -105,6 +116,14 @@ impl<'g, T, G: Protect<T>, const N: usize> FusedProtectedRef<'g, T, G, N> {
     pub fn into_guard_ref(self) -> &'g mut G {
         self.guard
     }
+
+    /// Consumes `self` and returns the borrowed guard after
+    /// [releasing][Protect::release] its currently protected value.
+    #[inline]
+    pub fn release(self) -> &'g mut G {
+        self.guard.release();
+        self.guard
+    }
 }
 
 /********** impl Debug ****************************************************************************/
@@ -157,10 +176,40 @@ impl<T, G: Protect<T>, const N: usize> FusedShared<T, G, N> {
         FusedProtected { guard: self.guard, protected: self.shared.into_marked_ptr() }
     }
 
+    /// De-references the [`Shared`] reference and projects it onto (e.g.)
+    /// one of its fields using `func`, keeping the guard alive.
+    ///
+    /// The returned [`FusedMapped`] can be used for at most the lifetime `'a`,
+    /// which should be bound to the data structure owning the record.
+    ///
+    /// # Safety
+    ///
+    /// See [`Shared::as_ref`] for an explanation of the safety concerns
+    /// involved in de-referencing a [`Shared`].
+    /// In addition, the record must not be de-allocated (e.g., by dropping its
+    /// owning data structure) during `'a`, since the guard only protects it
+    /// from concurrent reclamation.
+    #[inline]
+    pub unsafe fn map<'a, U>(self, func: impl FnOnce(&'a T) -> &'a U) -> FusedMapped<'a, U, G>
+    where
+        T: 'a,
+    {
+        let ptr = NonNull::from(func(&*self.shared.decompose_ptr()));
+        FusedMapped { guard: self.guard, ptr, _marker: PhantomData }
+    }
+
     #[inline]
     pub fn into_guard(self) -> G {
         self.guard
     }
+
+    /// Consumes `self` and returns the contained guard instance after
+    /// [releasing][Protect::release] its currently protected value.
+    #[inline]
+    pub fn release(mut self) -> G {
+        self.guard.release();
+        self.guard
+    }
 }
 
 /********** impl Debug ****************************************************************************/
@@ -211,10 +260,45 @@ impl<'g, T, G: Protect<T>, const N: usize> FusedSharedRef<'g, T, G, N> {
         unsafe { Shared::from_marked_non_null(self.shared) }
     }
 
+    /// De-references the [`Shared`] reference and projects it onto (e.g.)
+    /// one of its fields using `func`, keeping the guard borrowed.
+    ///
+    /// The returned [`FusedMappedRef`] can be used for at most the lifetime
+    /// `'a`, which is bound by the borrow of the guard and should also be
+    /// bound to the data structure owning the record.
+    ///
+    /// # Safety
+    ///
+    /// See [`Shared::as_ref`] for an explanation of the safety concerns
+    /// involved in de-referencing a [`Shared`].
+    /// In addition, the record must not be de-allocated (e.g., by dropping its
+    /// owning data structure) during `'a`, since the guard only protects it
+    /// from concurrent reclamation.
+    #[inline]
+    pub unsafe fn map<'a, U: 'a>(
+        self,
+        func: impl FnOnce(&'a T) -> &'a U,
+    ) -> FusedMappedRef<'a, U, G>
+    where
+        'g: 'a,
+        T: 'a,
+    {
+        let ptr = NonNull::from(func(&*self.shared.decompose_ptr()));
+        FusedMappedRef { guard: self.guard, ptr }
+    }
+
     #[inline]
     pub fn into_guard_ref(self) -> &'g mut G {
         self.guard
     }
+
+    /// Consumes `self` and returns the borrowed guard after
+    /// [releasing][Protect::release] its currently protected value.
+    #[inline]
+    pub fn release(self) -> &'g mut G {
+        self.guard.release();
+        self.guard
+    }
 }
 
 /********** impl Debug ****************************************************************************/
@@ -225,3 +309,115 @@ impl<T, G: Protect<T>, const N: usize> fmt::Debug for FusedSharedRef<'_, T, G, N
         write!(f, "FusedSharedRef {{ ... }}")
     }
 }
+
+// *************************************************************************************************
+// FusedMapped
+// *************************************************************************************************
+
+/// An owned guard fused with a reference projected from a protected value
+/// (see [`FusedShared::map`]).
+///
+/// The reference remains valid for as long as the `FusedMapped` is alive, but
+/// no longer than the lifetime `'a` of the data structure owning the value.
+pub struct FusedMapped<'a, U, G> {
+    guard: G,
+    ptr: NonNull<U>,
+    _marker: PhantomData<&'a U>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'a, U, G> FusedMapped<'a, U, G> {
+    /// Further projects the reference using `func`.
+    #[inline]
+    pub fn map<V>(self, func: impl FnOnce(&U) -> &V) -> FusedMapped<'a, V, G> {
+        let ptr = NonNull::from(func(&*self));
+        FusedMapped { guard: self.guard, ptr, _marker: PhantomData }
+    }
+
+    /// Consumes `self` and returns the contained guard instance, forfeiting the
+    /// projected reference.
+    #[inline]
+    pub fn into_guard(self) -> G {
+        self.guard
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<U: fmt::Debug, G> fmt::Debug for FusedMapped<'_, U, G> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FusedMapped").field(&**self).finish()
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<U, G> Deref for FusedMapped<'_, U, G> {
+    type Target = U;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // safety: the projected value is kept alive by the fused guard
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+// *************************************************************************************************
+// FusedMappedRef
+// *************************************************************************************************
+
+/// A borrowed guard fused with a reference projected from a protected value
+/// (see [`FusedSharedRef::map`]).
+pub struct FusedMappedRef<'g, U, G> {
+    guard: &'g mut G,
+    ptr: NonNull<U>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<'g, U, G> FusedMappedRef<'g, U, G> {
+    /// Further projects the reference using `func`.
+    #[inline]
+    pub fn map<V>(self, func: impl FnOnce(&U) -> &V) -> FusedMappedRef<'g, V, G> {
+        let ptr = NonNull::from(func(&*self));
+        FusedMappedRef { guard: self.guard, ptr }
+    }
+
+    /// Returns the projected reference, which remains valid for as long as the
+    /// guard is borrowed.
+    #[inline]
+    pub fn into_ref(self) -> &'g U {
+        // safety: the projected value is kept alive by the borrowed guard
+        unsafe { &*self.ptr.as_ptr() }
+    }
+
+    /// Consumes `self` and returns the borrowed guard, forfeiting the
+    /// projected reference.
+    #[inline]
+    pub fn into_guard_ref(self) -> &'g mut G {
+        self.guard
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<U: fmt::Debug, G> fmt::Debug for FusedMappedRef<'_, U, G> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FusedMappedRef").field(&**self).finish()
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<U, G> Deref for FusedMappedRef<'_, U, G> {
+    type Target = U;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        // safety: the projected value is kept alive by the borrowed guard
+        unsafe { self.ptr.as_ref() }
+    }
+}
@@ -0,0 +1,129 @@
+//! Guard types combining several individual guards.
+
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::sync::atomic::Ordering;
+
+use conquer_pointer::MarkedPtr;
+
+use crate::atomic::Atomic;
+use crate::traits::{Protect, ProtectMany};
+use crate::{NotEqual, Protected};
+
+// *************************************************************************************************
+// GuardArray
+// *************************************************************************************************
+
+/// An array of `K` guards, each of which is used as one (independent)
+/// protection slot.
+///
+/// Individual guards can be borrowed simultaneously through
+/// [`as_mut_array`][GuardArray::as_mut_array], e.g., by destructuring the
+/// returned array reference.
+#[derive(Clone)]
+pub struct GuardArray<G, const K: usize> {
+    guards: [G; K],
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<G, const K: usize> GuardArray<G, K> {
+    /// Creates a new [`GuardArray`] from the given array of `guards`.
+    #[inline]
+    pub fn new(guards: [G; K]) -> Self {
+        Self { guards }
+    }
+
+    /// Creates a new [`GuardArray`] by calling `func` with the index of each
+    /// slot, e.g., for building guards from a thread state.
+    ///
+    /// If `func` panics, all previously built guards are leaked.
+    #[inline]
+    pub fn from_fn(mut func: impl FnMut(usize) -> G) -> Self {
+        unsafe {
+            let mut guards: MaybeUninit<[G; K]> = MaybeUninit::uninit();
+            let ptr: *mut G = guards.as_mut_ptr().cast();
+            for idx in 0..K {
+                ptr.add(idx).write(func(idx));
+            }
+
+            Self { guards: guards.assume_init() }
+        }
+    }
+
+    /// Returns a mutable reference to the guard in the given `slot`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is out of bounds.
+    #[inline]
+    pub fn get_mut(&mut self, slot: usize) -> &mut G {
+        &mut self.guards[slot]
+    }
+
+    /// Returns a mutable reference to the array of all guards.
+    #[inline]
+    pub fn as_mut_array(&mut self) -> &mut [G; K] {
+        &mut self.guards
+    }
+
+    /// Consumes `self` and returns the array of all guards.
+    #[inline]
+    pub fn into_inner(self) -> [G; K] {
+        self.guards
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<G, const K: usize> fmt::Debug for GuardArray<G, K> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GuardArray").field("slots", &K).finish()
+    }
+}
+
+/********** impl ProtectMany **********************************************************************/
+
+unsafe impl<T, G: Protect<T>, const K: usize> ProtectMany<T> for GuardArray<G, K> {
+    type Reclaim = G::Reclaim;
+    const SLOTS: usize = K;
+
+    #[inline]
+    fn protect_slot<const N: usize>(
+        &mut self,
+        slot: usize,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        order: Ordering,
+    ) -> Protected<T, Self::Reclaim, N> {
+        self.guards[slot].protect(atomic, order)
+    }
+
+    #[inline]
+    fn protect_slot_if_equal<const N: usize>(
+        &mut self,
+        slot: usize,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> Result<Protected<T, Self::Reclaim, N>, NotEqual> {
+        self.guards[slot].protect_if_equal(atomic, expected, order)
+    }
+
+    #[inline]
+    fn swap_slots(&mut self, a: usize, b: usize) {
+        self.guards.swap(a, b);
+    }
+
+    #[inline]
+    fn shift(&mut self) {
+        if K > 0 {
+            self.guards.rotate_left(1);
+        }
+    }
+
+    #[inline]
+    fn release_slot(&mut self, slot: usize) {
+        self.guards[slot].release();
+    }
+}
@@ -19,6 +19,7 @@ use conquer_pointer::{MarkedNonNull, MarkedPtr};
 use crate::alias::AssocRecord;
 use crate::atomic::Storable;
 use crate::record::Record;
+use crate::tag::{self, Tag};
 use crate::traits::Reclaim;
 use crate::Owned;
 
@@ -42,6 +43,13 @@ where
     pub fn with_tag(value: T, tag: usize) -> Self {
         unsafe { Self::with_header_and_tag(Default::default(), value, tag) }
     }
+
+    /// Creates a new `Owned` like [`with_tag`](Owned::with_tag) but with a
+    /// typed initial `tag` value.
+    #[inline]
+    pub fn with_typed_tag<G: Tag>(value: T, tag: G) -> Self {
+        Self::with_tag(value, tag::into_raw::<G, N>(tag))
+    }
 }
 
 impl<T, R: Reclaim<T>, const N: usize> Owned<T, R, N> {
@@ -148,6 +156,19 @@ impl<T, R: Reclaim<T>, const N: usize> Owned<T, R, N> {
         owned.inner.decompose_tag()
     }
 
+    /// Sets the typed tag value to `tag`, overwriting any previous value.
+    #[inline]
+    pub fn set_typed_tag<G: Tag>(owned: Self, tag: G) -> Self {
+        Self::set_tag(owned, tag::into_raw::<G, N>(tag))
+    }
+
+    /// Decomposes the internal marked pointer, returning only the separated
+    /// typed tag value.
+    #[inline]
+    pub fn decompose_typed_tag<G: Tag>(owned: &Self) -> G {
+        tag::from_raw::<G, N>(Self::decompose_tag(owned))
+    }
+
     /// Decomposes the internal marked pointer, returning a reference and the
     /// separated tag.
     #[inline]
@@ -284,9 +305,37 @@ impl<T, R: Reclaim<T>, const N: usize> Drop for Owned<T, R, N> {
 impl<T, R: Reclaim<T>, const N: usize> From<Owned<T, R, N>> for Box<AssocRecord<T, R>> {
     #[inline]
     fn from(owned: Owned<T, R, N>) -> Self {
+        let owned = ManuallyDrop::new(owned);
         unsafe {
             let record = Owned::<T, R, N>::record_ptr(owned.inner.decompose_ptr());
             Box::from_raw(record)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::leak::Leaking;
+    use crate::Owned;
+
+    struct DropCounting<'a>(&'a AtomicUsize);
+
+    impl Drop for DropCounting<'_> {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn into_inner_drops_once() {
+        let count = AtomicUsize::new(0);
+        let owned = Owned::<_, Leaking, 0>::new(DropCounting(&count));
+
+        let value = Owned::into_inner(owned);
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        drop(value);
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+    }
+}
@@ -1,8 +1,10 @@
 use core::fmt;
 use core::marker::PhantomData;
+use core::ptr::NonNull;
 
 use conquer_pointer::{MarkedNonNull, MarkedPtr};
 
+use crate::counted::{CountedHeader, ReclaimArc};
 use crate::traits::Reclaim;
 use crate::{Protected, Shared};
 
@@ -108,6 +110,35 @@ impl<'g, T, R: Reclaim<T>, const N: usize> Shared<'g, T, R, N> {
     }
 }
 
+/********** impl inherent (counted) ***************************************************************/
+
+impl<'g, T, R: Reclaim<T>, const N: usize> Shared<'g, T, R, N>
+where
+    R::Header: CountedHeader,
+{
+    /// Upgrades the [`Shared`] reference into a reference counted
+    /// [`ReclaimArc`] handle, which keeps the referenced value alive even
+    /// after the guard protecting it is released.
+    ///
+    /// # Safety
+    ///
+    /// See [`as_ref`][Shared::as_ref] for an explanation of the safety concerns
+    /// involved in de-referencing a [`Shared`], which equally apply to the
+    /// returned handle.
+    /// In addition, the record must never be de-allocated or reused except
+    /// through retirement and subsequent reclamation, since only this path
+    /// respects the reference count.
+    /// In particular, the record must not be de-allocated by dropping an
+    /// [`Owned`][crate::Owned] obtained through (e.g.)
+    /// [`Atomic::take`][crate::Atomic::take] or
+    /// [`Unlinked::into_owned`][crate::Unlinked::into_owned] while any handle
+    /// may still be alive.
+    #[inline]
+    pub unsafe fn upgrade(self) -> ReclaimArc<T, R> {
+        ReclaimArc::acquire(NonNull::new_unchecked(self.inner.decompose_ptr()))
+    }
+}
+
 /********** impl Debug ****************************************************************************/
 
 impl<T: fmt::Debug, R, const N: usize> fmt::Debug for Shared<'_, T, R, N> {
@@ -6,9 +6,9 @@ use core::ptr;
 use conquer_pointer::{MarkedNonNull, MarkedPtr};
 
 use crate::retired::Retired;
-use crate::traits::Reclaim;
+use crate::traits::{Reclaim, ReclaimThreadState};
 
-use crate::Unlinked;
+use crate::{Owned, Unlinked};
 
 /********** impl inherent *************************************************************************/
 
@@ -54,6 +54,35 @@ impl<T, R: Reclaim<T>, const N: usize> Unlinked<T, R, N> {
             Retired::new_unchecked(retired)
         }
     }
+
+    /// Converts the unlinked record back into an [`Owned`] one for reuse,
+    /// e.g., for inserting it into another data structure without going
+    /// through the allocator, if `thread_state` reports that the record is
+    /// currently not protected by any thread (see
+    /// [`is_unprotected`][ReclaimThreadState::is_unprotected]).
+    ///
+    /// For reclamation mechanisms with a
+    /// [`CountedHeader`][crate::counted::CountedHeader], the record must also
+    /// not be referenced by any [`ReclaimArc`][crate::counted::ReclaimArc]
+    /// handle, which `is_unprotected` must account for (e.g., by checking the
+    /// record's reference count), since the returned [`Owned`] can be dropped
+    /// or reused without regard for any outstanding handles.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the record may still be protected, in which case `self` is
+    /// returned unchanged and has to be retired as usual.
+    #[inline]
+    pub fn into_owned(
+        self,
+        thread_state: &impl ReclaimThreadState<T, Reclaim = R>,
+    ) -> Result<Owned<T, R, N>, Self> {
+        if thread_state.is_unprotected(self.inner.decompose_ptr()) {
+            Ok(Owned { inner: self.inner, _marker: PhantomData })
+        } else {
+            Err(self)
+        }
+    }
 }
 
 /********** impl Debug ****************************************************************************/
@@ -67,3 +96,65 @@ impl<T, R, const N: usize> fmt::Debug for Unlinked<T, R, N> {
 impl<T, R, const N: usize> fmt::Pointer for Unlinked<T, R, N> {
     impl_fmt_pointer!();
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::ptr;
+
+    use conquer_pointer::MarkedPtr;
+
+    use crate::leak::{Guard, Leaking};
+    use crate::retired::Retired;
+    use crate::traits::{ReclaimRef, ReclaimThreadState};
+    use crate::{Owned, Unlinked};
+
+    /// A thread state, which tracks a single protected record.
+    struct Tracking {
+        protected: Cell<*const i32>,
+    }
+
+    unsafe impl ReclaimThreadState<i32> for Tracking {
+        type Reclaim = Leaking;
+        type Guard = Guard;
+
+        fn derived_from(&self, _: &impl ReclaimRef<i32, Reclaim = Leaking>) -> bool {
+            true
+        }
+
+        fn build_guard(&self) -> Guard {
+            Guard
+        }
+
+        fn alloc_owned<const N: usize>(&self, value: i32) -> Owned<i32, Leaking, N> {
+            Owned::new(value)
+        }
+
+        unsafe fn retire_record(&self, retired: Retired<Leaking>) {
+            let _ = retired;
+        }
+
+        fn is_unprotected(&self, record: *const i32) -> bool {
+            !ptr::eq(record, self.protected.get())
+        }
+    }
+
+    #[test]
+    fn into_owned() {
+        let state = Tracking { protected: Cell::new(ptr::null()) };
+        let ptr: MarkedPtr<_, 1> = Owned::into_marked_ptr(state.alloc_owned(1));
+        let unlinked = unsafe { Unlinked::<_, Leaking, 1>::from_marked_ptr(ptr) };
+
+        // the record is protected and can not be reused
+        state.protected.set(ptr.decompose_ptr());
+        let unlinked = unlinked.into_owned(&state).unwrap_err();
+        assert_eq!(unlinked.as_marked_ptr(), ptr);
+
+        // the record is no longer protected and can be reused
+        state.protected.set(ptr::null());
+        let mut owned = unlinked.into_owned(&state).unwrap();
+        assert_eq!(Owned::as_marked_ptr(&owned), ptr);
+        *owned = 2;
+        assert_eq!(*owned, 2);
+    }
+}
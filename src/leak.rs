@@ -1,11 +1,14 @@
 //! TODO: mod-level docs
 
 use core::marker::PhantomData;
-use core::sync::atomic::Ordering;
+use core::mem;
+use core::sync::atomic::{AtomicUsize, Ordering};
 
 use conquer_pointer::MarkedPtr;
 
+use crate::alias::AssocRecord;
 use crate::retired::Retired;
+use crate::stats::{ReclaimStats, Stats};
 use crate::traits::{Protect, Reclaim, ReclaimBase, ReclaimRef, ReclaimThreadState};
 use crate::NotEqual;
 
@@ -28,6 +31,11 @@ pub type Unlinked<T, const N: usize> = crate::Unlinked<T, Leaking, N>;
 /// [`Leaking`] as reclaimer.
 pub type Unprotected<T, const N: usize> = crate::Unprotected<T, Leaking, N>;
 
+/// The total number of records retired (and hence leaked) through [`Leaking`].
+static LEAKED_RECORDS: AtomicUsize = AtomicUsize::new(0);
+/// The combined size in bytes of all records leaked through [`Leaking`].
+static LEAKED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
 // *************************************************************************************************
 // Leaking
 // *************************************************************************************************
@@ -37,6 +45,8 @@ pub struct Leaking;
 
 /********** impl ReclaimBase **********************************************************************/
 
+// records are never reclaimed, so their sizes are counted in `retire_record`,
+// where their types are still known, rather than being stored in a header
 unsafe impl ReclaimBase for Leaking {
     type Header = ();
     type Retired = ();
@@ -89,8 +99,31 @@ unsafe impl<T> ReclaimThreadState<T> for Leaking {
         Owned::new(value)
     }
 
-    #[inline(always)]
-    unsafe fn retire_record(&self, _: Retired<Leaking>) {}
+    #[inline]
+    unsafe fn retire_record(&self, _: Retired<Leaking>) {
+        LEAKED_RECORDS.fetch_add(1, Ordering::Relaxed);
+        LEAKED_BYTES.fetch_add(mem::size_of::<AssocRecord<T, Leaking>>(), Ordering::Relaxed);
+    }
+}
+
+/********** impl ReclaimStats *********************************************************************/
+
+impl ReclaimStats for Leaking {
+    /// Returns the statistics for all records ever retired through any
+    /// [`Leaking`] instance, all of which are reported as pending, since none
+    /// of them will ever be reclaimed.
+    #[inline]
+    fn stats(&self) -> Stats {
+        let leaked = LEAKED_RECORDS.load(Ordering::Relaxed);
+        Stats {
+            retired: leaked,
+            reclaimed: 0,
+            pending: leaked,
+            pending_bytes: LEAKED_BYTES.load(Ordering::Relaxed),
+            scans: 0,
+            active_guards: 0,
+        }
+    }
 }
 
 // *************************************************************************************************
@@ -138,3 +171,35 @@ unsafe impl<T> Protect<T> for Guard {
 unsafe impl<T> Protect<T> for &Guard {
     impl_protect!();
 }
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+
+    use conquer_pointer::MarkedPtr;
+
+    use super::Leaking;
+    use crate::alias::AssocRecord;
+    use crate::stats::ReclaimStats;
+    use crate::traits::ReclaimThreadState;
+    use crate::{Owned, Unlinked};
+
+    #[test]
+    fn leaked_stats() {
+        let owned = Owned::<_, Leaking, 0>::new([1u64, 2, 3, 4]);
+        let ptr: MarkedPtr<_, 0> = Owned::into_marked_ptr(owned);
+        let retired = unsafe { Unlinked::<_, Leaking, 0>::from_marked_ptr(ptr) }.into_retired();
+        assert_eq!(unsafe { *retired.as_ptr().cast::<[u64; 4]>() }, [1, 2, 3, 4]);
+
+        // other tests may leak records concurrently
+        let before = Leaking.stats();
+        unsafe { <Leaking as ReclaimThreadState<[u64; 4]>>::retire_record(&Leaking, retired) };
+        let after = Leaking.stats();
+
+        let size = mem::size_of::<AssocRecord<[u64; 4], Leaking>>();
+        assert!(after.retired > before.retired);
+        assert!(after.pending_bytes >= before.pending_bytes + size);
+        assert_eq!(after.pending, after.retired);
+        assert_eq!(after.reclaimed, 0);
+    }
+}
@@ -2,6 +2,10 @@
 
 #![feature(min_const_generics, set_ptr_value)]
 #![cfg_attr(not(any(test, feature = "std")), no_std)]
+#![cfg_attr(
+    all(feature = "nightly", target_arch = "x86_64", target_feature = "cmpxchg16b"),
+    feature(cmpxchg16b_intrinsic)
+)]
 // #![warn(missing_docs)] todo: re-enable
 
 extern crate alloc;
@@ -9,12 +13,26 @@ extern crate alloc;
 #[macro_use]
 mod macros;
 
+pub mod array;
+pub mod bounded;
+pub mod cell;
+#[cfg(feature = "std")]
+pub mod collector;
+pub mod counted;
+pub mod cursor;
 #[macro_use]
 pub mod erased;
 #[cfg(feature = "examples")]
 pub mod examples;
 pub mod fused;
+pub mod guards;
 pub mod leak;
+pub mod observer;
+pub mod ordering;
+pub mod pool;
+pub mod region;
+pub mod stats;
+pub mod tag;
 
 mod alias;
 mod atomic;
@@ -31,10 +49,12 @@ use conquer_pointer::{MarkedNonNull, MarkedPtr};
 // public re-exports
 pub use conquer_pointer;
 
-pub use crate::atomic::{Atomic, Comparable, CompareExchangeErr, Storable};
-pub use crate::retired::Retired;
+pub use crate::atomic::{
+    Atomic, AtomicOwned, AtomicVersioned, Comparable, CompareExchangeErr, Storable, Versioned,
+};
+pub use crate::retired::{AtomicRetiredList, LinkedHeader, Retired, RetiredLink, RetiredList};
 pub use crate::traits::{
-    Protect, ProtectExt, Reclaim, ReclaimBase, ReclaimRef, ReclaimThreadState,
+    Protect, ProtectExt, ProtectMany, Reclaim, ReclaimBase, ReclaimRef, ReclaimThreadState,
 };
 
 // *************************************************************************************************
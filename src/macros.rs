@@ -11,6 +11,13 @@ macro_rules! default_null {
 
 macro_rules! impl_from_ptr {
     () => {
+        /// Creates a new instance from a raw marked pointer.
+        ///
+        /// # Safety
+        ///
+        /// `ptr` must be non-null and must point at the value of a record
+        /// allocated for the reclamation mechanism `R`, which fulfills the
+        /// invariants of the created type.
         #[inline]
         pub unsafe fn from_marked_ptr(ptr: MarkedPtr<T, N>) -> Self {
             Self { inner: MarkedNonNull::new_unchecked(ptr), _marker: PhantomData }
@@ -20,6 +27,13 @@ macro_rules! impl_from_ptr {
 
 macro_rules! impl_from_ptr_for_nullable {
     () => {
+        /// Creates a new instance from a raw marked pointer.
+        ///
+        /// # Safety
+        ///
+        /// `ptr` must either be null or point at the value of a record
+        /// allocated for the reclamation mechanism `R`, which fulfills the
+        /// invariants of the created type.
         #[inline]
         pub unsafe fn from_marked_ptr(ptr: MarkedPtr<T, N>) -> Self {
             Self { inner: ptr, _marker: PhantomData }
@@ -29,6 +43,13 @@ macro_rules! impl_from_ptr_for_nullable {
 
 macro_rules! impl_from_non_null {
     () => {
+        /// Creates a new instance from a raw marked non-null pointer.
+        ///
+        /// # Safety
+        ///
+        /// `ptr` must point at the value of a record allocated for the
+        /// reclamation mechanism `R`, which fulfills the invariants of the
+        /// created type.
         #[inline]
         pub unsafe fn from_marked_non_null(ptr: MarkedNonNull<T, N>) -> Self {
             Self { inner: ptr.into(), _marker: PhantomData }
@@ -77,6 +98,40 @@ macro_rules! impl_common {
         pub fn decompose_tag(self) -> usize {
             self.inner.decompose_tag()
         }
+
+        /// Splits the typed tag value and returns both the cleared pointer and
+        /// the separated tag value.
+        ///
+        /// Fails to compile, if `G` does not fit into the `N` tag bits.
+        /// Any raw tag value set previously is converted through
+        /// [`Tag::from_usize`][crate::tag::Tag::from_usize].
+        #[inline]
+        pub fn split_typed_tag<G: $crate::tag::Tag>(self) -> (Self, G) {
+            let (ptr, tag) = self.split_tag();
+            (ptr, $crate::tag::from_raw::<G, N>(tag))
+        }
+
+        /// Sets the typed tag value to `tag`, overwriting any previous value.
+        ///
+        /// Fails to compile, if `G` does not fit into the `N` tag bits.
+        /// Since the raw tag value is masked to `N` bits, a [`Tag`] that
+        /// returns raw values exceeding its declared number of bits can not
+        /// corrupt the pointer, but the stored tag is truncated.
+        ///
+        /// [`Tag`]: crate::tag::Tag
+        #[inline]
+        pub fn set_typed_tag<G: $crate::tag::Tag>(self, tag: G) -> Self {
+            self.set_tag($crate::tag::into_raw::<G, N>(tag))
+        }
+
+        /// Decomposes the marked pointer, returning only the separated typed
+        /// tag value.
+        ///
+        /// Fails to compile, if `G` does not fit into the `N` tag bits.
+        #[inline]
+        pub fn decompose_typed_tag<G: $crate::tag::Tag>(self) -> G {
+            $crate::tag::from_raw::<G, N>(self.decompose_tag())
+        }
     };
 }
 
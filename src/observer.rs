@@ -0,0 +1,168 @@
+//! A common instrumentation interface for reclamation mechanisms.
+//!
+//! Reclamation mechanisms can be made generic over a [`ReclaimObserver`] and
+//! invoke its callbacks at the appropriate points, with [`NoopObserver`] as
+//! the default, for which all callbacks compile away entirely.
+//! Since none of its methods are generic, the trait is also object safe, so
+//! observers can be plugged in as trait objects, e.g., as
+//! `&dyn ReclaimObserver`.
+
+use crate::stats::AtomicStats;
+
+// *************************************************************************************************
+// ReclaimObserver (trait)
+// *************************************************************************************************
+
+/// A trait for callbacks, which reclamation mechanisms invoke whenever a
+/// record is retired or reclaimed, a guard is created or dropped or a scan
+/// for reclaimable records is performed.
+///
+/// All methods have empty default implementations, so implementors only
+/// need to override the callbacks they are interested in.
+///
+/// Records are identified by the address of their data (see
+/// [`Retired::as_ptr`][crate::Retired::as_ptr]) and their `size` in bytes
+/// includes their header (see
+/// [`Retired::record_size`][crate::Retired::record_size]).
+/// Each event must be reported exactly once, i.e., only by the part of a
+/// reclamation mechanism that actually retires or reclaims a record.
+pub trait ReclaimObserver {
+    /// Called when the `record` of `size` bytes is handed over to the
+    /// reclamation mechanism.
+    #[inline(always)]
+    fn on_retire(&self, record: *mut (), size: usize) {
+        let _ = (record, size);
+    }
+
+    /// Called immediately before the `record` of `size` bytes is reclaimed,
+    /// while it is still valid.
+    ///
+    /// The address of `record` is the same as it was when
+    /// [`on_retire`][ReclaimObserver::on_retire] was called for it, which
+    /// allows e.g. measuring the delay between retirement and reclamation.
+    #[inline(always)]
+    fn on_reclaim(&self, record: *mut (), size: usize) {
+        let _ = (record, size);
+    }
+
+    /// Called when a guard is created.
+    #[inline(always)]
+    fn on_guard_created(&self) {}
+
+    /// Called when a guard is dropped.
+    #[inline(always)]
+    fn on_guard_dropped(&self) {}
+
+    /// Called after a scan (or epoch advance) has been performed, which
+    /// determined `reclaimed` records as reclaimable.
+    #[inline(always)]
+    fn on_scan(&self, reclaimed: usize) {
+        let _ = reclaimed;
+    }
+}
+
+/********** impl ReclaimObserver (&O) *************************************************************/
+
+impl<O: ReclaimObserver + ?Sized> ReclaimObserver for &O {
+    #[inline(always)]
+    fn on_retire(&self, record: *mut (), size: usize) {
+        (**self).on_retire(record, size);
+    }
+
+    #[inline(always)]
+    fn on_reclaim(&self, record: *mut (), size: usize) {
+        (**self).on_reclaim(record, size);
+    }
+
+    #[inline(always)]
+    fn on_guard_created(&self) {
+        (**self).on_guard_created();
+    }
+
+    #[inline(always)]
+    fn on_guard_dropped(&self) {
+        (**self).on_guard_dropped();
+    }
+
+    #[inline(always)]
+    fn on_scan(&self, reclaimed: usize) {
+        (**self).on_scan(reclaimed);
+    }
+}
+
+/********** impl ReclaimObserver ((A, B)) *********************************************************/
+
+/// Pairs of observers can be combined, in which case every callback is first
+/// invoked on the first and then on the second observer.
+impl<A: ReclaimObserver, B: ReclaimObserver> ReclaimObserver for (A, B) {
+    #[inline(always)]
+    fn on_retire(&self, record: *mut (), size: usize) {
+        self.0.on_retire(record, size);
+        self.1.on_retire(record, size);
+    }
+
+    #[inline(always)]
+    fn on_reclaim(&self, record: *mut (), size: usize) {
+        self.0.on_reclaim(record, size);
+        self.1.on_reclaim(record, size);
+    }
+
+    #[inline(always)]
+    fn on_guard_created(&self) {
+        self.0.on_guard_created();
+        self.1.on_guard_created();
+    }
+
+    #[inline(always)]
+    fn on_guard_dropped(&self) {
+        self.0.on_guard_dropped();
+        self.1.on_guard_dropped();
+    }
+
+    #[inline(always)]
+    fn on_scan(&self, reclaimed: usize) {
+        self.0.on_scan(reclaimed);
+        self.1.on_scan(reclaimed);
+    }
+}
+
+/********** impl ReclaimObserver (AtomicStats) ****************************************************/
+
+impl ReclaimObserver for AtomicStats {
+    #[inline]
+    fn on_retire(&self, _: *mut (), size: usize) {
+        self.record_retire(size);
+    }
+
+    #[inline]
+    fn on_reclaim(&self, _: *mut (), size: usize) {
+        self.record_reclaim(size);
+    }
+
+    #[inline]
+    fn on_guard_created(&self) {
+        self.record_guard_created();
+    }
+
+    #[inline]
+    fn on_guard_dropped(&self) {
+        self.record_guard_dropped();
+    }
+
+    #[inline]
+    fn on_scan(&self, _: usize) {
+        self.record_scan();
+    }
+}
+
+// *************************************************************************************************
+// NoopObserver
+// *************************************************************************************************
+
+/// An observer, which ignores all events.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct NoopObserver;
+
+/********** impl ReclaimObserver ******************************************************************/
+
+impl ReclaimObserver for NoopObserver {}
@@ -0,0 +1,347 @@
+//! Zero-sized memory ordering types and pointer wrappers that encode the
+//! memory ordering they were loaded with in their type.
+//!
+//! De-referencing a [`Shared`] or [`Protected`] is generally `unsafe`, since
+//! the caller has to ensure the value was loaded with (at least)
+//! [`Acquire`][core::sync::atomic::Ordering::Acquire] semantics and that it
+//! was published with (at least)
+//! [`Release`][core::sync::atomic::Ordering::Release] semantics.
+//! Loading a value with [`load_ordered`][crate::Atomic::load_ordered] and an
+//! [`AcquireOrdering`] type instead returns an [`Ordered`] pointer, which
+//! carries the proof for the former and can hence be de-referenced safely.
+//! The latter is ensured by only accepting orderings with (at least) *release*
+//! semantics for all operations publishing values through ordering types
+//! (see [`StoreOrdering`], [`SwapOrdering`] and [`CasOrderings`]).
+//!
+//! The ordering types also allow rejecting invalid orderings for the
+//! respective operations at compile time, which would otherwise result in
+//! panics at runtime.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::sync::atomic::Ordering;
+
+use conquer_pointer::{MarkedNonNull, Null};
+
+use crate::traits::Reclaim;
+use crate::{Maybe, Protected, Shared};
+
+// *************************************************************************************************
+// Relaxed, Acquire, Release, AcqRel & SeqCst
+// *************************************************************************************************
+
+/// A zero-sized type representing [`Ordering::Relaxed`].
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Relaxed;
+
+/// A zero-sized type representing [`Ordering::Acquire`].
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Acquire;
+
+/// A zero-sized type representing [`Ordering::Release`].
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Release;
+
+/// A zero-sized type representing [`Ordering::AcqRel`].
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct AcqRel;
+
+/// A zero-sized type representing [`Ordering::SeqCst`].
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct SeqCst;
+
+// *************************************************************************************************
+// LoadOrdering (trait)
+// *************************************************************************************************
+
+/// A (sealed) trait for all memory ordering types that are valid for load
+/// operations.
+pub trait LoadOrdering: sealed::Sealed {
+    /// The represented [`Ordering`].
+    const ORDER: Ordering;
+}
+
+impl LoadOrdering for Relaxed {
+    const ORDER: Ordering = Ordering::Relaxed;
+}
+
+impl LoadOrdering for Acquire {
+    const ORDER: Ordering = Ordering::Acquire;
+}
+
+impl LoadOrdering for SeqCst {
+    const ORDER: Ordering = Ordering::SeqCst;
+}
+
+// *************************************************************************************************
+// AcquireOrdering (trait)
+// *************************************************************************************************
+
+/// A (sealed) marker trait for all load orderings with (at least) *acquire*
+/// semantics.
+pub trait AcquireOrdering: LoadOrdering {}
+
+impl AcquireOrdering for Acquire {}
+impl AcquireOrdering for SeqCst {}
+
+// *************************************************************************************************
+// StoreOrdering (trait)
+// *************************************************************************************************
+
+/// A (sealed) trait for all memory ordering types that are valid for store
+/// operations, which *publish* the stored value.
+///
+/// Only orderings with (at least) *release* semantics are valid, since a
+/// [`Relaxed`] store would not publish the stored value, i.e., [`Ordered`]
+/// pointers loaded from it could not be de-referenced safely.
+pub trait StoreOrdering: sealed::Sealed {
+    /// The represented [`Ordering`].
+    const ORDER: Ordering;
+}
+
+impl StoreOrdering for Release {
+    const ORDER: Ordering = Ordering::Release;
+}
+
+impl StoreOrdering for SeqCst {
+    const ORDER: Ordering = Ordering::SeqCst;
+}
+
+// *************************************************************************************************
+// SwapOrdering (trait)
+// *************************************************************************************************
+
+/// A (sealed) trait for all memory ordering types that are valid for swap
+/// operations, which *publish* the swapped in value.
+///
+/// Like for [`StoreOrdering`], only orderings with (at least) *release*
+/// semantics are valid.
+pub trait SwapOrdering: sealed::Sealed {
+    /// The represented [`Ordering`].
+    const ORDER: Ordering;
+}
+
+impl SwapOrdering for Release {
+    const ORDER: Ordering = Ordering::Release;
+}
+
+impl SwapOrdering for AcqRel {
+    const ORDER: Ordering = Ordering::AcqRel;
+}
+
+impl SwapOrdering for SeqCst {
+    const ORDER: Ordering = Ordering::SeqCst;
+}
+
+// *************************************************************************************************
+// CasOrderings (trait)
+// *************************************************************************************************
+
+/// A (sealed) trait for all pairs of memory ordering types that are valid for
+/// *compare-and-swap* operations, which *publish* the swapped in value.
+///
+/// The trait is implemented for tuples of the form `(Success, Failure)`, for
+/// which the `Success` ordering has (at least) *release* semantics and the
+/// `Failure` ordering is a valid [`LoadOrdering`] and not stronger than the
+/// `Success` ordering.
+pub trait CasOrderings: sealed::Sealed {
+    /// The represented [`Ordering`] in case of success.
+    const SUCCESS: Ordering;
+    /// The represented [`Ordering`] in case of failure.
+    const FAILURE: Ordering;
+}
+
+macro_rules! impl_cas_orderings {
+    ($(($success:ident, $failure:ident)),*) => {
+        $(
+            impl sealed::Sealed for ($success, $failure) {}
+
+            impl CasOrderings for ($success, $failure) {
+                const SUCCESS: Ordering = Ordering::$success;
+                const FAILURE: Ordering = Ordering::$failure;
+            }
+        )*
+    };
+}
+
+impl_cas_orderings!(
+    (Release, Relaxed),
+    (AcqRel, Relaxed),
+    (AcqRel, Acquire),
+    (SeqCst, Relaxed),
+    (SeqCst, Acquire),
+    (SeqCst, SeqCst)
+);
+
+// *************************************************************************************************
+// Ordered
+// *************************************************************************************************
+
+/// A wrapper for a [`Protected`] or [`Shared`] pointer, which has been loaded
+/// with the memory ordering represented by `O`.
+///
+/// If `O` implements [`AcquireOrdering`], the wrapped pointer can be
+/// de-referenced safely.
+/// This relies on all values being *published* (i.e., stored into an
+/// [`Atomic`][crate::Atomic] after being initialized) with (at least)
+/// [`Release`][Ordering::Release] semantics, which all operations taking
+/// ordering types enforce at compile time.
+/// Values stored through operations taking an [`Ordering`] argument with
+/// weaker semantics must hence be published by other means, e.g., by a
+/// subsequent release store of a record containing the
+/// [`Atomic`][crate::Atomic].
+#[derive(Eq, Ord, PartialEq, PartialOrd)]
+pub struct Ordered<P, O> {
+    pub(crate) inner: P,
+    _marker: PhantomData<O>,
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl<P: Copy, O> Clone for Ordered<P, O> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.inner)
+    }
+}
+
+/********** impl Copy *****************************************************************************/
+
+impl<P: Copy, O> Copy for Ordered<P, O> {}
+
+/********** impl inherent *************************************************************************/
+
+impl<P, O> Ordered<P, O> {
+    /// Returns the inner pointer, forfeiting the proof of its ordering.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+
+    #[inline]
+    pub(crate) fn new(inner: P) -> Self {
+        Self { inner, _marker: PhantomData }
+    }
+}
+
+/********** impl inherent (Protected) *************************************************************/
+
+impl<'g, T, R: Reclaim<T>, O, const N: usize> Ordered<Protected<'g, T, R, N>, O> {
+    /// Returns `true` if the inner pointer is `null`.
+    #[inline]
+    pub fn is_null(self) -> bool {
+        self.inner.is_null()
+    }
+
+    /// Converts the inner pointer into a [`Shared`], if it is non-null.
+    #[inline]
+    pub fn shared(self) -> Maybe<Ordered<Shared<'g, T, R, N>, O>> {
+        match MarkedNonNull::new(self.inner.inner) {
+            Ok(inner) => Maybe::Some(Ordered::new(Shared { inner, _marker: PhantomData })),
+            Err(Null(tag)) => Maybe::Null(tag),
+        }
+    }
+}
+
+impl<'g, T, R: Reclaim<T>, O: AcquireOrdering, const N: usize> Ordered<Protected<'g, T, R, N>, O> {
+    /// De-references the inner pointer, if it is non-null.
+    #[inline]
+    pub fn as_ref(self) -> Option<&'g T> {
+        unsafe { self.inner.as_ref() }
+    }
+
+    /// Decomposes and de-references the inner pointer and returns both the
+    /// (optional) reference and its tag value.
+    #[inline]
+    pub fn decompose_ref(self) -> (Option<&'g T>, usize) {
+        unsafe { self.inner.decompose_ref() }
+    }
+}
+
+/********** impl inherent (Shared) ****************************************************************/
+
+impl<'g, T, R: Reclaim<T>, O: AcquireOrdering, const N: usize> Ordered<Shared<'g, T, R, N>, O> {
+    /// De-references the inner reference.
+    #[inline]
+    pub fn as_ref(self) -> &'g T {
+        unsafe { self.inner.as_ref() }
+    }
+
+    /// Decomposes and de-references the inner reference and returns both the
+    /// reference and its tag value.
+    #[inline]
+    pub fn decompose_ref(self) -> (&'g T, usize) {
+        unsafe { self.inner.decompose_ref() }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<P: fmt::Debug, O: LoadOrdering> fmt::Debug for Ordered<P, O> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Ordered").field("inner", &self.inner).field("order", &O::ORDER).finish()
+    }
+}
+
+/********** impl Deref ****************************************************************************/
+
+impl<'g, T, R: Reclaim<T>, O: AcquireOrdering, const N: usize> Deref
+    for Ordered<Shared<'g, T, R, N>, O>
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_ref()
+    }
+}
+
+/********** impl Pointer **************************************************************************/
+
+impl<P: fmt::Pointer, O> fmt::Pointer for Ordered<P, O> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&self.inner, f)
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Relaxed {}
+    impl Sealed for super::Acquire {}
+    impl Sealed for super::Release {}
+    impl Sealed for super::AcqRel {}
+    impl Sealed for super::SeqCst {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::leak::{Guard, Leaking};
+    use crate::{Atomic, Maybe, Owned};
+
+    use super::{Acquire, Release, SeqCst};
+
+    #[test]
+    fn ordered_deref() {
+        let atomic: Atomic<i32, Leaking, 0> = Atomic::null();
+        atomic.store_ordered(Owned::new(1), Release);
+
+        let mut guard = Guard;
+        let ordered = atomic.load_ordered(&mut guard, Acquire);
+        assert_eq!(ordered.as_ref(), Some(&1));
+        match ordered.shared() {
+            Maybe::Some(shared) => assert_eq!(*shared, 1),
+            Maybe::Null(_) => panic!("unexpected null pointer"),
+        }
+
+        match atomic.swap_ordered(Owned::new(2), SeqCst) {
+            Maybe::Some(prev) => assert_eq!(unsafe { *prev.as_ref() }, 1),
+            Maybe::Null(_) => panic!("unexpected null pointer"),
+        }
+
+        assert_eq!(atomic.load_ordered(&mut guard, SeqCst).decompose_ref(), (Some(&2), 0));
+    }
+}
@@ -0,0 +1,449 @@
+//! Type-stable pools of records, which allow reclamation mechanisms to recycle
+//! reclaimed records instead of de-allocating them.
+//!
+//! Reclamation mechanisms opt into this functionality by reserving a
+//! [`RetiredLink`] in their [`Header`][ReclaimBase::Header] type (see
+//! [`LinkedHeader`]), which is used for chaining the records in the pool.
+//! A [`RecordPool`] is typically stored in a `static` item (for mechanisms
+//! with a concrete [`Retired`][ReclaimBase::Retired] type) or in the global
+//! state of a mechanism, so that:
+//!
+//! - [`ReclaimBase::reclaim`] can return records to the pool through
+//!   [`recycle`][RecordPool::recycle] and
+//! - [`ReclaimRef::alloc_owned`][crate::ReclaimRef::alloc_owned] can reuse
+//!   pooled records through [`alloc_owned`][RecordPool::alloc_owned].
+
+use core::fmt;
+use core::hint;
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
+use conquer_pointer::MarkedNonNull;
+
+use crate::alias::RetiredRecord;
+use crate::record::Record;
+use crate::retired::{LinkedHeader, RetiredLink};
+use crate::traits::{Reclaim, ReclaimBase};
+use crate::Owned;
+
+// *************************************************************************************************
+// RecordPool
+// *************************************************************************************************
+
+/// A lock-free pool of (uninitialized) records of reclamation mechanism `R`,
+/// which holds at most [`cap`][RecordPool::cap] records at a time.
+///
+/// Records are pushed to the pool without any locking, whereas popping
+/// records is guarded by a *try-lock*, which avoids the ABA problem inherent
+/// to lock-free pop operations:
+/// If the lock is currently held by another thread, a new record is allocated
+/// instead of waiting for the lock to become available.
+pub struct RecordPool<R: ReclaimBase>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    head: AtomicPtr<()>,
+    len: AtomicUsize,
+    cap: AtomicUsize,
+    popping: AtomicBool,
+    _marker: PhantomData<R>,
+}
+
+/********** impl Send + Sync **********************************************************************/
+
+unsafe impl<R: ReclaimBase> Send for RecordPool<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+}
+
+unsafe impl<R: ReclaimBase> Sync for RecordPool<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+}
+
+/********** impl inherent (const) *****************************************************************/
+
+impl<R: ReclaimBase> RecordPool<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    /// Creates a new empty [`RecordPool`] without any capacity limit.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with_cap(usize::MAX)
+    }
+
+    /// Creates a new empty [`RecordPool`], which holds at most `cap` records
+    /// at a time.
+    #[inline]
+    pub const fn with_cap(cap: usize) -> Self {
+        Self {
+            head: AtomicPtr::new(ptr::null_mut()),
+            len: AtomicUsize::new(0),
+            cap: AtomicUsize::new(cap),
+            popping: AtomicBool::new(false),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<R: ReclaimBase> RecordPool<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    /// Returns the (approximate) number of records currently in the pool.
+    ///
+    /// Records which are concurrently being returned to the pool are already
+    /// included in this number.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the pool is currently empty, i.e., if its
+    /// [`len`][RecordPool::len] is 0.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the maximum number of records held by the pool.
+    #[inline]
+    pub fn cap(&self) -> usize {
+        self.cap.load(Ordering::Relaxed)
+    }
+
+    /// Sets the maximum number of records held by the pool.
+    ///
+    /// Records in excess of a lowered `cap` are not de-allocated until
+    /// [`trim`][RecordPool::trim] is called.
+    #[inline]
+    pub fn set_cap(&self, cap: usize) {
+        self.cap.store(cap, Ordering::Relaxed);
+    }
+
+    /// Allocates an owned record with a default header and the given `value`,
+    /// reusing a pooled record if possible.
+    #[inline]
+    pub fn alloc_owned<T, const N: usize>(&self, value: T) -> Owned<T, R, N>
+    where
+        R: Reclaim<T> + ReclaimBase<Retired = T>,
+        R::Header: Default,
+    {
+        unsafe { self.alloc_owned_with_header(Default::default(), value) }
+    }
+
+    /// Allocates an owned record with the given `header` and `value`, reusing
+    /// a pooled record if possible, in which case the record's previous header
+    /// is dropped and replaced.
+    ///
+    /// # Safety
+    ///
+    /// The same safety requirements as for [`Owned::with_header`] apply.
+    #[inline]
+    pub unsafe fn alloc_owned_with_header<T, const N: usize>(
+        &self,
+        header: R::Header,
+        value: T,
+    ) -> Owned<T, R, N>
+    where
+        R: Reclaim<T> + ReclaimBase<Retired = T>,
+    {
+        match self.pop(false) {
+            Some(data) => {
+                let record = RetiredRecord::<R>::record_from_data(data.as_ptr());
+                (*record).header = header;
+                data.as_ptr().write(value);
+                Owned::from_marked_non_null(MarkedNonNull::compose_unchecked(data, 0))
+            }
+            None => Owned::with_header(header, value),
+        }
+    }
+
+    /// Drops the value of the `retired` record in place and returns the
+    /// record to the pool or de-allocates it, if the pool is full.
+    ///
+    /// Reclamation mechanisms using a [`RecordPool`] should call this function
+    /// in their implementation of [`ReclaimBase::reclaim`].
+    ///
+    /// # Safety
+    ///
+    /// The same safety requirements as for [`ReclaimBase::reclaim`] apply.
+    /// Additionally, the record's [`RetiredLink`] must no longer be in use,
+    /// e.g., by any [`RetiredList`][crate::RetiredList].
+    #[inline]
+    pub unsafe fn recycle(&self, retired: *mut R::Retired) {
+        ptr::drop_in_place(retired);
+        if self.len.fetch_add(1, Ordering::Relaxed) >= self.cap() {
+            self.len.fetch_sub(1, Ordering::Relaxed);
+            dealloc::<R>(NonNull::new_unchecked(retired));
+            return;
+        }
+
+        let mut curr = self.head.load(Ordering::Relaxed);
+        loop {
+            link::<R>(retired).store_next(curr);
+            match self.head.compare_exchange_weak(
+                curr,
+                retired.cast(),
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return,
+                Err(actual) => curr = actual,
+            }
+        }
+    }
+
+    /// De-allocates pooled records until at most `keep` records remain in the
+    /// pool and returns the number of de-allocated records.
+    ///
+    /// Records may be allocated or returned by other threads while the pool is
+    /// trimmed, so the pool is not guaranteed to hold exactly `keep` records
+    /// afterwards.
+    #[inline]
+    pub fn trim(&self, keep: usize) -> usize {
+        let mut count = 0;
+        while self.len() > keep {
+            match self.pop(true) {
+                // safety: the record's value has been dropped and the record is no longer pooled
+                Some(data) => unsafe { dealloc::<R>(data) },
+                None => break,
+            }
+
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Attempts to pop a record from the pool.
+    ///
+    /// If the pop lock is held by another thread, [`None`] is returned right
+    /// away, unless `wait` is `true`, in which case the lock is awaited, so
+    /// that [`None`] is only returned if the pool is empty.
+    #[inline]
+    fn pop(&self, wait: bool) -> Option<NonNull<R::Retired>> {
+        while self.popping.swap(true, Ordering::Acquire) {
+            if !wait || self.head.load(Ordering::Relaxed).is_null() {
+                return None;
+            }
+
+            hint::spin_loop();
+        }
+
+        // since only one thread at a time can pop records, the head record can not be popped and
+        // pushed again while it is being accessed here
+        let mut curr = self.head.load(Ordering::Acquire);
+        let popped = loop {
+            let data = match NonNull::new(curr.cast::<R::Retired>()) {
+                Some(data) => data,
+                None => break None,
+            };
+
+            let next = unsafe { link::<R>(data.as_ptr()) }.load_next();
+            match self.head.compare_exchange_weak(curr, next, Ordering::Acquire, Ordering::Acquire)
+            {
+                Ok(_) => break Some(data),
+                Err(actual) => curr = actual,
+            }
+        };
+
+        self.popping.store(false, Ordering::Release);
+        if popped.is_some() {
+            self.len.fetch_sub(1, Ordering::Relaxed);
+        }
+
+        popped
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<R: ReclaimBase> fmt::Debug for RecordPool<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RecordPool")
+            .field("len", &self.len.load(Ordering::Relaxed))
+            .field("cap", &self.cap.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<R: ReclaimBase> Default for RecordPool<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<R: ReclaimBase> Drop for RecordPool<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    #[inline]
+    fn drop(&mut self) {
+        let mut curr = *self.head.get_mut();
+        while let Some(data) = NonNull::new(curr.cast::<R::Retired>()) {
+            unsafe {
+                curr = link::<R>(data.as_ptr()).load_next();
+                dealloc::<R>(data);
+            }
+        }
+    }
+}
+
+/********** helper functions **********************************************************************/
+
+#[inline]
+unsafe fn link<'a, R: ReclaimBase>(retired: *mut R::Retired) -> &'a RetiredLink
+where
+    R::Header: LinkedHeader,
+{
+    (*R::as_header_ptr(retired)).retired_link()
+}
+
+#[inline]
+unsafe fn dealloc<R: ReclaimBase>(data: NonNull<R::Retired>)
+where
+    R::Retired: Sized,
+{
+    // the value has already been dropped, so the record is de-allocated without dropping it again
+    let record = RetiredRecord::<R>::record_from_data(data.as_ptr());
+    mem::drop(Box::from_raw(record as *mut Record<R::Header, ManuallyDrop<R::Retired>>));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use conquer_pointer::MarkedPtr;
+
+    use super::RecordPool;
+    use crate::retired::{LinkedHeader, RetiredLink};
+    use crate::traits::{Reclaim, ReclaimBase};
+    use crate::{Owned, Unlinked};
+
+    static POOL: RecordPool<Pooled> = RecordPool::with_cap(1);
+
+    struct DropCounting(&'static AtomicUsize, u32);
+
+    impl Drop for DropCounting {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[derive(Default)]
+    struct Header {
+        link: RetiredLink,
+    }
+
+    unsafe impl LinkedHeader for Header {
+        fn retired_link(&self) -> &RetiredLink {
+            &self.link
+        }
+    }
+
+    struct Pooled;
+
+    unsafe impl ReclaimBase for Pooled {
+        type Header = Header;
+        type Retired = DropCounting;
+
+        unsafe fn reclaim(retired: *mut DropCounting) {
+            POOL.recycle(retired);
+        }
+    }
+
+    unsafe impl Reclaim<DropCounting> for Pooled {
+        unsafe fn retire(ptr: *mut DropCounting) -> *mut DropCounting {
+            ptr
+        }
+    }
+
+    fn reclaim(owned: Owned<DropCounting, Pooled, 0>) {
+        let ptr: MarkedPtr<_, 0> = Owned::into_marked_ptr(owned);
+        unsafe { Unlinked::<_, Pooled, 0>::from_marked_ptr(ptr).into_retired().reclaim() };
+    }
+
+    #[test]
+    fn recycle_records() {
+        static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+        let first = POOL.alloc_owned::<_, 0>(DropCounting(&DROP_COUNT, 1));
+        let second = POOL.alloc_owned::<_, 0>(DropCounting(&DROP_COUNT, 2));
+        let addr = Owned::as_marked_ptr(&first);
+
+        reclaim(first);
+        reclaim(second);
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 2);
+        assert_eq!(POOL.len(), 1);
+
+        let reused = POOL.alloc_owned::<_, 0>(DropCounting(&DROP_COUNT, 3));
+        assert_eq!(Owned::as_marked_ptr(&reused), addr);
+        assert_eq!(reused.1, 3);
+        assert!(POOL.is_empty());
+
+        reclaim(reused);
+        assert_eq!(POOL.trim(0), 1);
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn trim_contended() {
+        static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+        let pool = Arc::new(RecordPool::<Pooled>::new());
+        for value in 0..2 {
+            let owned = Owned::<_, Pooled, 0>::new(DropCounting(&DROP_COUNT, value));
+            let ptr: MarkedPtr<_, 0> = Owned::into_marked_ptr(owned);
+            unsafe { pool.recycle(ptr.decompose_ptr()) };
+        }
+
+        assert_eq!(pool.len(), 2);
+        assert!(!pool.is_empty());
+
+        // hold the pop lock as if another thread were currently popping
+        pool.popping.store(true, Ordering::Relaxed);
+        let trim = {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || pool.trim(0))
+        };
+
+        thread::sleep(Duration::from_millis(10));
+        pool.popping.store(false, Ordering::Release);
+
+        assert_eq!(trim.join().unwrap(), 2);
+        assert!(pool.is_empty());
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 2);
+    }
+}
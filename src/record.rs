@@ -57,7 +57,7 @@ impl<H, T: ?Sized> Record<H, T> {
     /// a given `data_align` of that field's type (of which the concrete type
     /// may not be known).
     #[inline]
-    const fn data_offset(data_align: usize) -> usize {
+    pub const fn data_offset(data_align: usize) -> usize {
         // this matches the layout algorithm used by `rustc` for C-like structs.
         let offset = Self::HEADER_OFFSET + mem::size_of::<H>();
         offset + offset.wrapping_neg() % data_align
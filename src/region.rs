@@ -0,0 +1,186 @@
+//! Region-based protection, which protects all values loaded within a
+//! (critical) region at once.
+//!
+//! Epoch-based reclamation mechanisms protect every value that is loaded
+//! between *pinning* and *unpinning* a thread, whereas the [`Protect`] trait
+//! models the protection of individual pointers.
+//! Mechanisms of the former kind can implement [`ProtectRegion`] for their
+//! per-thread state, so that generic data structures can enter one region per
+//! operation through [`pin`][ProtectRegion::pin] and then use the cheap
+//! [`RegionProtect`] adapter for all loads within that region, instead of
+//! creating one guard per load.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::sync::atomic::Ordering;
+
+use conquer_pointer::MarkedPtr;
+
+use crate::atomic::Atomic;
+use crate::leak::Leaking;
+use crate::traits::{Protect, Reclaim, ReclaimBase};
+use crate::{NotEqual, Protected};
+
+// *************************************************************************************************
+// ProtectRegion (trait)
+// *************************************************************************************************
+
+/// A trait for (per-thread) types of reclamation mechanisms, which protect all
+/// values loaded within a region delimited by [`enter`][ProtectRegion::enter]
+/// and [`leave`][ProtectRegion::leave].
+///
+/// # Safety
+///
+/// No value that is loaded from any [`Atomic`] associated to the same
+/// reclamation mechanism after entering a region must be reclaimed before the
+/// region is left again.
+/// Regions must be able to be nested, i.e., only leaving the outermost region
+/// may end the protection.
+pub unsafe trait ProtectRegion {
+    /// The associated reclamation mechanism.
+    type Reclaim: ReclaimBase;
+
+    /// Enters a (possibly nested) region.
+    ///
+    /// # Safety
+    ///
+    /// Every call to `enter` must be paired with exactly one subsequent call
+    /// to [`leave`][ProtectRegion::leave] on the same thread.
+    unsafe fn enter(&self);
+
+    /// Leaves the most recently entered region.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called after a corresponding call to
+    /// [`enter`][ProtectRegion::enter] and no values loaded within the region
+    /// must be used after it has been left.
+    unsafe fn leave(&self);
+
+    /// Enters a region, which is left again when the returned [`RegionGuard`]
+    /// is dropped.
+    #[inline]
+    fn pin(&self) -> RegionGuard<Self>
+    where
+        Self: Sized,
+    {
+        unsafe { self.enter() };
+        RegionGuard { region: self, _marker: PhantomData }
+    }
+}
+
+/********** impl Leaking **************************************************************************/
+
+unsafe impl ProtectRegion for Leaking {
+    type Reclaim = Self;
+
+    #[inline(always)]
+    unsafe fn enter(&self) {}
+
+    #[inline(always)]
+    unsafe fn leave(&self) {}
+}
+
+// *************************************************************************************************
+// RegionGuard
+// *************************************************************************************************
+
+/// A guard for an entered region, which leaves the region when it is dropped.
+pub struct RegionGuard<'r, P: ProtectRegion> {
+    region: &'r P,
+    /// Regions are thread-local, hence the guard is neither `Send` nor `Sync`.
+    _marker: PhantomData<*mut ()>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<P: ProtectRegion> RegionGuard<'_, P> {
+    /// Returns a [`Protect`] adapter for loading values within the region.
+    #[inline]
+    pub fn protector(&self) -> RegionProtect<P> {
+        RegionProtect { guard: self }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<P: ProtectRegion> fmt::Debug for RegionGuard<'_, P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RegionGuard {{ ... }}")
+    }
+}
+
+/********** impl Drop *****************************************************************************/
+
+impl<P: ProtectRegion> Drop for RegionGuard<'_, P> {
+    #[inline]
+    fn drop(&mut self) {
+        // safety: the region has been entered when the guard was created and all values loaded
+        // within it are bound to the lifetime of the guard
+        unsafe { self.region.leave() };
+    }
+}
+
+// *************************************************************************************************
+// RegionProtect
+// *************************************************************************************************
+
+/// A cheap (`Copy`) [`Protect`] adapter for loading values within an entered
+/// region, which requires no per-pointer protection at all.
+pub struct RegionProtect<'a, P: ProtectRegion> {
+    guard: &'a RegionGuard<'a, P>,
+}
+
+/********** impl Clone ****************************************************************************/
+
+impl<P: ProtectRegion> Clone for RegionProtect<'_, P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self { guard: self.guard }
+    }
+}
+
+/********** impl Copy *****************************************************************************/
+
+impl<P: ProtectRegion> Copy for RegionProtect<'_, P> {}
+
+/********** impl Debug ****************************************************************************/
+
+impl<P: ProtectRegion> fmt::Debug for RegionProtect<'_, P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RegionProtect {{ ... }}")
+    }
+}
+
+/********** impl Protect **************************************************************************/
+
+unsafe impl<T, P: ProtectRegion> Protect<T> for RegionProtect<'_, P>
+where
+    P::Reclaim: Reclaim<T>,
+{
+    type Reclaim = P::Reclaim;
+
+    #[inline]
+    fn protect<const N: usize>(
+        &mut self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        order: Ordering,
+    ) -> Protected<T, Self::Reclaim, N> {
+        // safety: all values loaded within the region are protected until it is left
+        unsafe { Protected::from_marked_ptr(atomic.load_raw(order)) }
+    }
+
+    #[inline]
+    fn protect_if_equal<const N: usize>(
+        &mut self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> Result<Protected<T, Self::Reclaim, N>, NotEqual> {
+        atomic
+            .load_raw_if_equal(expected, order)
+            .map(|ptr| unsafe { Protected::from_marked_ptr(ptr) })
+    }
+}
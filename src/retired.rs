@@ -1,9 +1,13 @@
+mod list;
+
 use core::cmp;
 use core::fmt;
 use core::ptr::NonNull;
 
 use crate::traits::ReclaimBase;
 
+pub use self::list::{AtomicRetiredList, LinkedHeader, RetiredLink, RetiredList};
+
 // *************************************************************************************************
 // Retired
 // *************************************************************************************************
@@ -25,6 +29,20 @@ impl<R: ReclaimBase> Retired<R> {
         unsafe { R::as_header_ptr(self.ptr.as_ptr()) }
     }
 
+    /// Returns the size in bytes of the entire retired record, including its
+    /// header.
+    #[inline]
+    pub fn record_size(&self) -> usize {
+        unsafe { R::record_size(self.ptr.as_ptr()) }
+    }
+
+    /// Reclaims the retired record, dropping its value and de-allocating it.
+    ///
+    /// # Safety
+    ///
+    /// The record must no longer be accessed by any thread (i.e., no thread
+    /// may still hold a protected reference to it) and it must not be
+    /// reclaimed more than once, so `self` must not be used again afterwards.
     #[inline]
     pub unsafe fn reclaim(&mut self) {
         R::reclaim(self.ptr.as_ptr());
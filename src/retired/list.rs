@@ -0,0 +1,589 @@
+//! Intrusive singly-linked lists of retired records, which are chained
+//! through a link field reserved in each record's header.
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::retired::Retired;
+use crate::traits::ReclaimBase;
+
+// *************************************************************************************************
+// LinkedHeader (trait)
+// *************************************************************************************************
+
+/// A trait for [`Header`][ReclaimBase::Header] types, which reserve a
+/// [`RetiredLink`] for chaining their records into [`RetiredList`]s or
+/// [`AtomicRetiredList`]s.
+///
+/// # Safety
+///
+/// The returned link must be part of the header itself and must not be used
+/// for any other purpose by the reclamation mechanism while the header's
+/// record is retired.
+pub unsafe trait LinkedHeader {
+    /// Returns a reference to the header's link field.
+    fn retired_link(&self) -> &RetiredLink;
+}
+
+// *************************************************************************************************
+// RetiredLink
+// *************************************************************************************************
+
+/// A link field for chaining retired records through their own headers.
+///
+/// The link stores a *thin* pointer to the next retired record, which is why
+/// it can only be used with reclamation mechanisms having a sized
+/// [`Retired`][ReclaimBase::Retired] type.
+pub struct RetiredLink {
+    next: AtomicPtr<()>,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl RetiredLink {
+    /// Creates a new unlinked [`RetiredLink`].
+    #[inline]
+    pub const fn new() -> Self {
+        Self { next: AtomicPtr::new(ptr::null_mut()) }
+    }
+
+    #[inline]
+    pub(crate) fn load_next(&self) -> *mut () {
+        self.next.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub(crate) fn store_next(&self, next: *mut ()) {
+        self.next.store(next, Ordering::Relaxed);
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl Default for RetiredLink {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl fmt::Debug for RetiredLink {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetiredLink").field("next", &self.next.load(Ordering::Relaxed)).finish()
+    }
+}
+
+// *************************************************************************************************
+// RetiredList
+// *************************************************************************************************
+
+/// A thread-local intrusive list of [`Retired`] records.
+///
+/// Pushing or popping records never allocates, since every record is linked
+/// through the [`RetiredLink`] in its own header.
+/// Records are popped in LIFO order.
+///
+/// Dropping a list does not reclaim the records it still contains, since it
+/// can not know whether this would be safe, so these records are leaked.
+/// Use [`reclaim_all`][RetiredList::reclaim_all] or [`drain`][RetiredList::drain]
+/// to reclaim them instead.
+pub struct RetiredList<R: ReclaimBase> {
+    head: Option<NonNull<R::Retired>>,
+    tail: Option<NonNull<R::Retired>>,
+    len: usize,
+}
+
+/********** impl inherent (const) *****************************************************************/
+
+impl<R: ReclaimBase> RetiredList<R> {
+    /// Creates a new empty [`RetiredList`].
+    #[inline]
+    pub const fn new() -> Self {
+        Self { head: None, tail: None, len: 0 }
+    }
+
+    /// Returns the number of records in the list.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list contains no records.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<R: ReclaimBase> RetiredList<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    /// Pushes `retired` to the front of the list.
+    #[inline]
+    pub fn push(&mut self, retired: Retired<R>) {
+        unsafe { set_next::<R>(retired.ptr, self.head) };
+        if self.tail.is_none() {
+            self.tail = Some(retired.ptr);
+        }
+
+        self.head = Some(retired.ptr);
+        self.len += 1;
+    }
+
+    /// Pops the record from the front of the list or returns [`None`] if the
+    /// list is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<Retired<R>> {
+        self.head.map(|head| {
+            self.head = unsafe { next::<R>(head) };
+            if self.head.is_none() {
+                self.tail = None;
+            }
+
+            self.len -= 1;
+            Retired { ptr: head }
+        })
+    }
+
+    /// Moves all records in `other` to the front of `self`.
+    #[inline]
+    pub fn append(&mut self, mut other: Self) {
+        if let Some(tail) = other.tail.take() {
+            unsafe { set_next::<R>(tail, self.head) };
+            if self.tail.is_none() {
+                self.tail = Some(tail);
+            }
+
+            self.head = other.head.take();
+            self.len += other.len;
+        }
+    }
+
+    /// Removes all records from the list and returns them as an iterator.
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, R> {
+        Drain { list: self }
+    }
+
+    /// Reclaims all records in the list.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as for [`Retired::reclaim`] apply to every record
+    /// in the list.
+    #[inline]
+    pub unsafe fn reclaim_all(&mut self) {
+        for mut retired in self.drain() {
+            retired.reclaim();
+        }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<R: ReclaimBase> fmt::Debug for RetiredList<R> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetiredList").field("len", &self.len).finish()
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<R: ReclaimBase> Default for RetiredList<R> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/********** impl Extend ***************************************************************************/
+
+impl<R: ReclaimBase> Extend<Retired<R>> for RetiredList<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = Retired<R>>>(&mut self, iter: I) {
+        for retired in iter {
+            self.push(retired);
+        }
+    }
+}
+
+/********** impl IntoIterator *********************************************************************/
+
+impl<R: ReclaimBase> IntoIterator for RetiredList<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    type Item = Retired<R>;
+    type IntoIter = IntoIter<R>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+// *************************************************************************************************
+// AtomicRetiredList
+// *************************************************************************************************
+
+/// A lock-free intrusive list of [`Retired`] records, which can be shared
+/// between threads.
+///
+/// Records can be pushed individually or as entire [`RetiredList`]s, but they
+/// can only be removed all at once through [`steal_all`][Self::steal_all],
+/// which avoids the ABA problem inherent to lock-free pop operations.
+///
+/// Since records may be stolen and subsequently reclaimed by any thread with
+/// access to the list, pushing records is `unsafe` and requires the types of
+/// all pushed records to be [`Send`].
+/// Like [`RetiredList`], the list leaks all records it still contains when it
+/// is dropped.
+pub struct AtomicRetiredList<R: ReclaimBase> {
+    head: AtomicPtr<()>,
+    _marker: PhantomData<R>,
+}
+
+/********** impl Send + Sync **********************************************************************/
+
+unsafe impl<R: ReclaimBase> Send for AtomicRetiredList<R> {}
+unsafe impl<R: ReclaimBase> Sync for AtomicRetiredList<R> {}
+
+/********** impl inherent (const) *****************************************************************/
+
+impl<R: ReclaimBase> AtomicRetiredList<R> {
+    /// Creates a new empty [`AtomicRetiredList`].
+    #[inline]
+    pub const fn new() -> Self {
+        Self { head: AtomicPtr::new(ptr::null_mut()), _marker: PhantomData }
+    }
+}
+
+/********** impl inherent *************************************************************************/
+
+impl<R: ReclaimBase> AtomicRetiredList<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    /// Returns `true` if the list is currently empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Relaxed).is_null()
+    }
+
+    /// Pushes `retired` to the front of the list.
+    ///
+    /// # Safety
+    ///
+    /// Since the record may be stolen and reclaimed by another thread, its
+    /// type must be [`Send`].
+    #[inline]
+    pub unsafe fn push(&self, retired: Retired<R>) {
+        self.push_chain(retired.ptr, retired.ptr);
+    }
+
+    /// Pushes all records in `list` to the front of the list using a single
+    /// successful *compare-and-swap* operation.
+    ///
+    /// # Safety
+    ///
+    /// Since the records may be stolen and reclaimed by another thread, their
+    /// types must be [`Send`].
+    #[inline]
+    pub unsafe fn push_list(&self, mut list: RetiredList<R>) {
+        if let (Some(head), Some(tail)) = (list.head.take(), list.tail.take()) {
+            self.push_chain(head, tail);
+        }
+    }
+
+    /// Removes all records currently in the list and returns them as a
+    /// thread-local [`RetiredList`].
+    #[inline]
+    pub fn steal_all(&self) -> RetiredList<R> {
+        let head = self.head.swap(ptr::null_mut(), Ordering::Acquire);
+        let mut list = RetiredList::new();
+        if let Some(head) = NonNull::new(head.cast::<R::Retired>()) {
+            let (mut tail, mut len) = (head, 1);
+            while let Some(next) = unsafe { next::<R>(tail) } {
+                tail = next;
+                len += 1;
+            }
+
+            list = RetiredList { head: Some(head), tail: Some(tail), len };
+        }
+
+        list
+    }
+
+    #[inline]
+    unsafe fn push_chain(&self, head: NonNull<R::Retired>, tail: NonNull<R::Retired>) {
+        let mut curr = self.head.load(Ordering::Relaxed);
+        loop {
+            set_next::<R>(tail, NonNull::new(curr.cast()));
+            match self.head.compare_exchange_weak(
+                curr,
+                head.as_ptr().cast(),
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return,
+                Err(actual) => curr = actual,
+            }
+        }
+    }
+}
+
+/********** impl Debug ****************************************************************************/
+
+impl<R: ReclaimBase> fmt::Debug for AtomicRetiredList<R> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AtomicRetiredList")
+            .field("head", &self.head.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+/********** impl Default **************************************************************************/
+
+impl<R: ReclaimBase> Default for AtomicRetiredList<R> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// *************************************************************************************************
+// Drain
+// *************************************************************************************************
+
+/// A draining iterator over the records of a [`RetiredList`].
+#[derive(Debug)]
+pub struct Drain<'a, R: ReclaimBase> {
+    list: &'a mut RetiredList<R>,
+}
+
+/********** impl Iterator *************************************************************************/
+
+impl<R: ReclaimBase> Iterator for Drain<'_, R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    type Item = Retired<R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+/********** impl ExactSizeIterator ****************************************************************/
+
+impl<R: ReclaimBase> ExactSizeIterator for Drain<'_, R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+}
+
+/********** impl FusedIterator ********************************************************************/
+
+impl<R: ReclaimBase> FusedIterator for Drain<'_, R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+}
+
+// *************************************************************************************************
+// IntoIter
+// *************************************************************************************************
+
+/// An owning iterator over the records of a [`RetiredList`].
+#[derive(Debug)]
+pub struct IntoIter<R: ReclaimBase> {
+    list: RetiredList<R>,
+}
+
+/********** impl Iterator *************************************************************************/
+
+impl<R: ReclaimBase> Iterator for IntoIter<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    type Item = Retired<R>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+/********** impl ExactSizeIterator ****************************************************************/
+
+impl<R: ReclaimBase> ExactSizeIterator for IntoIter<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+}
+
+/********** impl FusedIterator ********************************************************************/
+
+impl<R: ReclaimBase> FusedIterator for IntoIter<R>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+}
+
+/********** helper functions **********************************************************************/
+
+#[inline]
+unsafe fn link<'a, R: ReclaimBase>(retired: NonNull<R::Retired>) -> &'a RetiredLink
+where
+    R::Header: LinkedHeader,
+{
+    (*R::as_header_ptr(retired.as_ptr())).retired_link()
+}
+
+#[inline]
+unsafe fn next<R: ReclaimBase>(retired: NonNull<R::Retired>) -> Option<NonNull<R::Retired>>
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    NonNull::new(link::<R>(retired).next.load(Ordering::Relaxed).cast())
+}
+
+#[inline]
+unsafe fn set_next<R: ReclaimBase>(retired: NonNull<R::Retired>, next: Option<NonNull<R::Retired>>)
+where
+    R::Header: LinkedHeader,
+    R::Retired: Sized,
+{
+    let next = next.map_or(ptr::null_mut(), |next| next.as_ptr().cast());
+    link::<R>(retired).next.store(next, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use conquer_pointer::MarkedPtr;
+
+    use crate::traits::{Reclaim, ReclaimBase};
+    use crate::{Owned, Retired, Unlinked};
+
+    use super::{AtomicRetiredList, LinkedHeader, RetiredLink, RetiredList};
+
+    static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    struct DropCounting(u32);
+
+    impl Drop for DropCounting {
+        fn drop(&mut self) {
+            DROP_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[derive(Default)]
+    struct Header {
+        link: RetiredLink,
+    }
+
+    unsafe impl LinkedHeader for Header {
+        fn retired_link(&self) -> &RetiredLink {
+            &self.link
+        }
+    }
+
+    struct Linked;
+
+    unsafe impl ReclaimBase for Linked {
+        type Header = Header;
+        type Retired = DropCounting;
+    }
+
+    unsafe impl Reclaim<DropCounting> for Linked {
+        unsafe fn retire(ptr: *mut DropCounting) -> *mut DropCounting {
+            ptr
+        }
+    }
+
+    fn retired(value: u32) -> Retired<Linked> {
+        let owned = Owned::<_, Linked, 0>::new(DropCounting(value));
+        let ptr: MarkedPtr<_, 0> = Owned::into_marked_ptr(owned);
+        unsafe { Unlinked::from_marked_ptr(ptr) }.into_retired()
+    }
+
+    fn value(retired: &Retired<Linked>) -> u32 {
+        unsafe { (*retired.as_ptr().cast::<DropCounting>()).0 }
+    }
+
+    #[test]
+    fn retired_lists() {
+        let mut list = RetiredList::new();
+        list.extend((0..3).map(retired));
+        assert_eq!(list.len(), 3);
+
+        let mut popped = list.pop().unwrap();
+        assert_eq!(value(&popped), 2);
+        unsafe { popped.reclaim() };
+
+        let mut other = RetiredList::new();
+        other.push(retired(3));
+        list.append(other);
+
+        let shared = AtomicRetiredList::new();
+        unsafe {
+            shared.push(retired(4));
+            shared.push_list(list);
+        }
+        assert!(!shared.is_empty());
+
+        let mut stolen = shared.steal_all();
+        assert!(shared.is_empty());
+        assert_eq!(stolen.len(), 4);
+
+        let values: Vec<_> = stolen
+            .drain()
+            .map(|mut retired| {
+                let value = value(&retired);
+                unsafe { retired.reclaim() };
+                value
+            })
+            .collect();
+        assert_eq!(values, [3, 1, 0, 4]);
+        assert!(stolen.is_empty());
+        assert_eq!(DROP_COUNT.load(Ordering::Relaxed), 5);
+    }
+}
@@ -0,0 +1,174 @@
+//! Statistics and introspection for reclamation mechanisms.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+// *************************************************************************************************
+// ReclaimStats (trait)
+// *************************************************************************************************
+
+/// A trait for global or per-thread states of reclamation mechanisms, which
+/// are able to report [`Stats`] about the records they manage.
+///
+/// Implementing this trait is optional, but it allows observing the growth of
+/// retired but not yet reclaimed records (garbage), which would otherwise be
+/// impossible.
+pub trait ReclaimStats {
+    /// Returns a snapshot of the current statistics.
+    ///
+    /// The individual counters are not required to be consistent with each
+    /// other, since they may be updated concurrently.
+    fn stats(&self) -> Stats;
+}
+
+// *************************************************************************************************
+// Stats
+// *************************************************************************************************
+
+/// A snapshot of the statistics reported by a [`ReclaimStats`] implementation.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Stats {
+    /// The total number of retired records.
+    pub retired: usize,
+    /// The total number of reclaimed records.
+    pub reclaimed: usize,
+    /// The number of retired records, which have not yet been reclaimed.
+    pub pending: usize,
+    /// The combined size in bytes of all pending records (including their
+    /// headers).
+    pub pending_bytes: usize,
+    /// The total number of scans (or epoch advances) that were performed in
+    /// order to determine reclaimable records.
+    pub scans: usize,
+    /// The number of currently active guards.
+    pub active_guards: usize,
+}
+
+// *************************************************************************************************
+// AtomicStats
+// *************************************************************************************************
+
+/// A set of atomic counters, which can be embedded in the state of a
+/// reclamation mechanism in order to implement [`ReclaimStats`].
+///
+/// All counters are updated using [`Relaxed`][Ordering::Relaxed] memory
+/// orderings.
+#[derive(Debug, Default)]
+pub struct AtomicStats {
+    retired: AtomicUsize,
+    reclaimed: AtomicUsize,
+    pending_bytes: AtomicUsize,
+    scans: AtomicUsize,
+    active_guards: AtomicUsize,
+}
+
+/********** impl inherent *************************************************************************/
+
+impl AtomicStats {
+    /// Creates a new set of counters, which are all initialized to zero.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            retired: AtomicUsize::new(0),
+            reclaimed: AtomicUsize::new(0),
+            pending_bytes: AtomicUsize::new(0),
+            scans: AtomicUsize::new(0),
+            active_guards: AtomicUsize::new(0),
+        }
+    }
+
+    /// Records the retirement of a record of `size` bytes.
+    #[inline]
+    pub fn record_retire(&self, size: usize) {
+        self.retired.fetch_add(1, Ordering::Relaxed);
+        self.pending_bytes.fetch_add(size, Ordering::Relaxed);
+    }
+
+    /// Records the reclamation of a previously retired record of `size` bytes.
+    ///
+    /// The number of pending bytes saturates at zero, so reclamations without
+    /// matching retirements can not cause it to wrap around.
+    #[inline]
+    pub fn record_reclaim(&self, size: usize) {
+        self.reclaimed.fetch_add(1, Ordering::Relaxed);
+        let _ = self.pending_bytes.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bytes| {
+            Some(bytes.saturating_sub(size))
+        });
+    }
+
+    /// Records a scan (or epoch advance).
+    #[inline]
+    pub fn record_scan(&self) {
+        self.scans.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records the creation of a guard.
+    #[inline]
+    pub fn record_guard_created(&self) {
+        self.active_guards.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records the destruction of a guard.
+    #[inline]
+    pub fn record_guard_dropped(&self) {
+        self.active_guards.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/********** impl ReclaimStats *********************************************************************/
+
+impl ReclaimStats for AtomicStats {
+    #[inline]
+    fn stats(&self) -> Stats {
+        let reclaimed = self.reclaimed.load(Ordering::Relaxed);
+        let retired = self.retired.load(Ordering::Relaxed);
+        Stats {
+            retired,
+            reclaimed,
+            pending: retired.saturating_sub(reclaimed),
+            pending_bytes: self.pending_bytes.load(Ordering::Relaxed),
+            scans: self.scans.load(Ordering::Relaxed),
+            active_guards: self.active_guards.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AtomicStats, ReclaimStats, Stats};
+
+    #[test]
+    fn atomic_stats() {
+        let stats = AtomicStats::new();
+        assert_eq!(stats.stats(), Stats::default());
+
+        stats.record_retire(32);
+        stats.record_retire(64);
+        stats.record_reclaim(32);
+        stats.record_scan();
+        stats.record_guard_created();
+        stats.record_guard_created();
+        stats.record_guard_dropped();
+
+        let expected = Stats {
+            retired: 2,
+            reclaimed: 1,
+            pending: 1,
+            pending_bytes: 64,
+            scans: 1,
+            active_guards: 1,
+        };
+        assert_eq!(stats.stats(), expected);
+    }
+
+    #[test]
+    fn unmatched_reclaim() {
+        let stats = AtomicStats::new();
+        stats.record_retire(16);
+        stats.record_reclaim(16);
+        stats.record_reclaim(16);
+
+        let snapshot = stats.stats();
+        assert_eq!(snapshot.pending, 0);
+        assert_eq!(snapshot.pending_bytes, 0);
+    }
+}
@@ -0,0 +1,121 @@
+//! Typed pointer tags.
+//!
+//! All marked pointer types store their tag as a raw `usize` of `N` bits.
+//! Types implementing the [`Tag`] trait (e.g., field-less enums) can be used
+//! in place of these raw values through the `*_typed_tag` methods, which
+//! check at compile time that the tag type fits into the `N` available bits.
+
+use core::marker::PhantomData;
+
+// *************************************************************************************************
+// Tag (trait)
+// *************************************************************************************************
+
+/// A trait for types that can be used as (typed) pointer tags.
+///
+/// # Example
+///
+/// ```
+/// use conquer_reclaim::tag::Tag;
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// enum Color {
+///     Red,
+///     Green,
+///     Blue,
+/// }
+///
+/// impl Tag for Color {
+///     const BITS: usize = 2;
+///
+///     fn into_usize(self) -> usize {
+///         self as usize
+///     }
+///
+///     fn from_usize(tag: usize) -> Self {
+///         match tag {
+///             0 => Color::Red,
+///             1 => Color::Green,
+///             _ => Color::Blue,
+///         }
+///     }
+/// }
+/// ```
+pub trait Tag: Copy {
+    /// The number of bits required to represent all values of the type.
+    const BITS: usize;
+
+    /// Converts the tag into its raw representation.
+    ///
+    /// The returned value must be representable in [`BITS`][Tag::BITS] bits.
+    fn into_usize(self) -> usize;
+
+    /// Converts a raw tag value into the typed tag.
+    ///
+    /// This function must accept any value that can be represented in
+    /// [`BITS`][Tag::BITS] bits, since raw tags can also be set through the
+    /// untyped API.
+    fn from_usize(tag: usize) -> Self;
+}
+
+/********** impl bool *****************************************************************************/
+
+impl Tag for bool {
+    const BITS: usize = 1;
+
+    #[inline]
+    fn into_usize(self) -> usize {
+        self as usize
+    }
+
+    #[inline]
+    fn from_usize(tag: usize) -> Self {
+        tag != 0
+    }
+}
+
+/********** impl () *******************************************************************************/
+
+impl Tag for () {
+    const BITS: usize = 0;
+
+    #[inline]
+    fn into_usize(self) -> usize {
+        0
+    }
+
+    #[inline]
+    fn from_usize(_: usize) -> Self {}
+}
+
+// *************************************************************************************************
+// helper functions
+// *************************************************************************************************
+
+/// Converts `tag` into its raw representation for a marked pointer with `N`
+/// tag bits.
+///
+/// Fails to compile, if `G` does not fit into `N` bits.
+#[inline(always)]
+pub(crate) fn into_raw<G: Tag, const N: usize>(tag: G) -> usize {
+    let _ = Fits::<G, N>::CHECK;
+    tag.into_usize()
+}
+
+/// Converts the raw `tag` of a marked pointer with `N` tag bits into `G`.
+///
+/// Fails to compile, if `G` does not fit into `N` bits.
+#[inline(always)]
+pub(crate) fn from_raw<G: Tag, const N: usize>(tag: usize) -> G {
+    let _ = Fits::<G, N>::CHECK;
+    G::from_usize(tag)
+}
+
+/// A type for checking whether a [`Tag`] type `G` fits into `N` bits.
+struct Fits<G, const N: usize>(PhantomData<G>);
+
+impl<G: Tag, const N: usize> Fits<G, N> {
+    /// Overflows during (post-monomorphization) constant evaluation, if `G`
+    /// requires more than `N` bits.
+    const CHECK: usize = N - G::BITS;
+}
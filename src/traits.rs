@@ -1,3 +1,4 @@
+use core::mem;
 use core::ops::Deref;
 use core::sync::atomic::Ordering;
 
@@ -7,8 +8,10 @@ use alloc::boxed::Box;
 use conquer_pointer::MarkedPtr;
 
 use crate::alias::RetiredRecord;
-use crate::atomic::Atomic;
+use crate::atomic::{Atomic, AtomicVersioned, Versioned};
+use crate::bounded::RetireError;
 use crate::fused::{FusedProtected, FusedProtectedRef};
+use crate::ordering::{LoadOrdering, Ordered};
 use crate::{NotEqual, Owned, Protected, Retired};
 
 /********** macros ********************************************************************************/
@@ -85,6 +88,18 @@ pub unsafe trait ReclaimBase: Sync + Sized {
     unsafe fn as_header_ptr(retired: *mut Self::Retired) -> *mut Self::Header {
         RetiredRecord::<Self>::header_from_data(retired)
     }
+
+    /// Returns the size in bytes of the entire `retired` record, including its
+    /// header.
+    ///
+    /// # Safety
+    ///
+    /// `retired` must point at a live record that was allocated through the
+    /// same memory reclamation type and was later retired.
+    #[inline]
+    unsafe fn record_size(retired: *mut Self::Retired) -> usize {
+        mem::size_of_val(&*RetiredRecord::<Self>::record_from_data(retired))
+    }
 }
 
 // *************************************************************************************************
@@ -186,6 +201,44 @@ pub unsafe trait ReclaimThreadState<T> {
     fn alloc_owned<const N: usize>(&self, value: T) -> Owned<T, Self::Reclaim, N>;
     /// Retires an [`Unlinked`][crate::Unlinked] memory record.
     unsafe fn retire_record(&self, retired: Retired<Self::Reclaim>);
+    /// Returns `true` if the unlinked `record` is currently not protected by
+    /// any thread and could hence be reclaimed (or reused) right away.
+    ///
+    /// Implementations must only ever return `true`, if no thread other than
+    /// the caller can access the record's value through any protected
+    /// reference at the time of the call, since this allows converting an
+    /// [`Unlinked`][crate::Unlinked] record back into an [`Owned`] one (see
+    /// [`into_owned`][crate::Unlinked::into_owned]).
+    /// Likewise, for mechanisms with a
+    /// [`CountedHeader`][crate::counted::CountedHeader], implementations must
+    /// only return `true`, if no [`ReclaimArc`][crate::counted::ReclaimArc]
+    /// handle references the record either.
+    ///
+    /// The default implementation conservatively always returns `false`.
+    #[inline]
+    fn is_unprotected(&self, _record: *const T) -> bool {
+        false
+    }
+    /// Attempts to retire an [`Unlinked`][crate::Unlinked] memory record,
+    /// which may fail if the reclamation mechanism enforces a
+    /// [`PendingLimit`][crate::bounded::PendingLimit] with the
+    /// [`Fail`][crate::bounded::OverflowPolicy::Fail] policy.
+    ///
+    /// The default implementation always succeeds and simply calls
+    /// [`retire_record`][ReclaimThreadState::retire_record].
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the record could not be retired, in which case it is
+    /// returned as part of the [`RetireError`].
+    #[inline]
+    unsafe fn try_retire_record(
+        &self,
+        retired: Retired<Self::Reclaim>,
+    ) -> Result<(), RetireError<Self::Reclaim>> {
+        self.retire_record(retired);
+        Ok(())
+    }
 }
 
 // *************************************************************************************************
@@ -219,6 +272,55 @@ pub unsafe trait Protect<T>: Clone {
         expected: MarkedPtr<T, N>,
         order: Ordering,
     ) -> Result<Protected<T, Self::Reclaim, N>, NotEqual>;
+
+    /// Loads and protects the value currently stored in `src` with the memory
+    /// ordering represented by `O` and returns an [`Ordered`] protected
+    /// pointer to it.
+    #[inline]
+    fn protect_ordered<O: LoadOrdering, const N: usize>(
+        &mut self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        _: O,
+    ) -> Ordered<Protected<T, Self::Reclaim, N>, O> {
+        Ordered::new(self.protect(atomic, O::ORDER))
+    }
+
+    /// Loads and protects the value currently stored in `src` with the memory
+    /// ordering represented by `O` if it equals the `expected` value and
+    /// returns an [`Ordered`] protected pointer to it.
+    #[inline]
+    fn protect_if_equal_ordered<O: LoadOrdering, const N: usize>(
+        &mut self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        expected: MarkedPtr<T, N>,
+        _: O,
+    ) -> Result<Ordered<Protected<T, Self::Reclaim, N>, O>, NotEqual> {
+        self.protect_if_equal(atomic, expected, O::ORDER).map(Ordered::new)
+    }
+
+    /// Loads and protects the value currently stored in the versioned `atomic`
+    /// if both its pointer and its version equal the `expected` snapshot and
+    /// returns a protected [`Shared`](crate::Shared) pointer to it.
+    ///
+    /// See [`AtomicVersioned::load_if_equal`] for further details.
+    #[inline]
+    fn protect_if_equal_versioned<const N: usize>(
+        &mut self,
+        atomic: &AtomicVersioned<T, Self::Reclaim, N>,
+        expected: Versioned<T, N>,
+        order: Ordering,
+    ) -> Result<Protected<T, Self::Reclaim, N>, NotEqual> {
+        atomic.load_if_equal(expected, self, order)
+    }
+
+    /// Releases the protection of the currently protected value, if any, while
+    /// keeping the guard itself (and any resources it holds) for later reuse.
+    ///
+    /// Since this requires `&mut self`, all [`Protected`] pointers borrowed
+    /// from the guard are invalidated by calling `release`.
+    /// The default implementation does nothing.
+    #[inline]
+    fn release(&mut self) {}
 }
 
 // *************************************************************************************************
@@ -251,6 +353,39 @@ pub trait ProtectExt<T>: Protect<T> {
         expected: MarkedPtr<T, N>,
         order: Ordering,
     ) -> Result<FusedProtectedRef<T, Self, N>, NotEqual>;
+
+    fn protect_fused_ordered<O: LoadOrdering, const N: usize>(
+        self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        _: O,
+    ) -> FusedProtected<T, Self, N>;
+
+    fn protect_fused_if_equal_ordered<O: LoadOrdering, const N: usize>(
+        self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        expected: MarkedPtr<T, N>,
+        _: O,
+    ) -> Result<FusedProtected<T, Self, N>, (Self, NotEqual)>;
+
+    fn protect_fused_ref_ordered<O: LoadOrdering, const N: usize>(
+        &mut self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        _: O,
+    ) -> FusedProtectedRef<T, Self, N>;
+
+    fn protect_fused_ref_if_equal_ordered<O: LoadOrdering, const N: usize>(
+        &mut self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        expected: MarkedPtr<T, N>,
+        _: O,
+    ) -> Result<FusedProtectedRef<T, Self, N>, NotEqual>;
+
+    fn protect_fused_if_equal_versioned<const N: usize>(
+        self,
+        atomic: &AtomicVersioned<T, Self::Reclaim, N>,
+        expected: Versioned<T, N>,
+        order: Ordering,
+    ) -> Result<FusedProtected<T, Self, N>, (Self, NotEqual)>;
 }
 
 /********** blanket impl **************************************************************************/
@@ -304,4 +439,150 @@ where
             _ => Err(NotEqual),
         }
     }
+
+    #[inline]
+    fn protect_fused_ordered<O: LoadOrdering, const N: usize>(
+        self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        _: O,
+    ) -> FusedProtected<T, Self, N> {
+        self.protect_fused(atomic, O::ORDER)
+    }
+
+    #[inline]
+    fn protect_fused_if_equal_ordered<O: LoadOrdering, const N: usize>(
+        self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        expected: MarkedPtr<T, N>,
+        _: O,
+    ) -> Result<FusedProtected<T, Self, N>, (Self, NotEqual)> {
+        self.protect_fused_if_equal(atomic, expected, O::ORDER)
+    }
+
+    #[inline]
+    fn protect_fused_ref_ordered<O: LoadOrdering, const N: usize>(
+        &mut self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        _: O,
+    ) -> FusedProtectedRef<T, Self, N> {
+        self.protect_fused_ref(atomic, O::ORDER)
+    }
+
+    #[inline]
+    fn protect_fused_ref_if_equal_ordered<O: LoadOrdering, const N: usize>(
+        &mut self,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        expected: MarkedPtr<T, N>,
+        _: O,
+    ) -> Result<FusedProtectedRef<T, Self, N>, NotEqual> {
+        self.protect_fused_ref_if_equal(atomic, expected, O::ORDER)
+    }
+
+    #[inline]
+    fn protect_fused_if_equal_versioned<const N: usize>(
+        mut self,
+        atomic: &AtomicVersioned<T, Self::Reclaim, N>,
+        expected: Versioned<T, N>,
+        order: Ordering,
+    ) -> Result<FusedProtected<T, Self, N>, (Self, NotEqual)> {
+        match self.protect_if_equal_versioned(atomic, expected, order) {
+            Ok(protected) => {
+                let protected = protected.into_marked_ptr();
+                Ok(FusedProtected { guard: self, protected })
+            }
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+// *************************************************************************************************
+// ProtectMany (trait)
+// *************************************************************************************************
+
+/// A trait for guard types holding a fixed number of independent protection
+/// slots, each of which protects (at most) one value at a time.
+///
+/// Hand-over-hand traversals can protect the values they currently visit in
+/// separate slots and rotate them (see [`shift`][ProtectMany::shift]) when
+/// advancing, without having to move individual guards around.
+/// Reclamation mechanisms based on hazard pointers can map the slots directly
+/// to hazard pointers.
+///
+/// # Safety
+///
+/// Protecting a value in one slot must not affect the protection of values in
+/// any other slot and rotating or swapping slots must preserve their
+/// respective protections.
+pub unsafe trait ProtectMany<T> {
+    /// The associated reclamation mechanism.
+    type Reclaim: Reclaim<T>;
+    /// The number of protection slots.
+    const SLOTS: usize;
+
+    /// Loads and protects the value currently stored in `atomic` in the given
+    /// `slot`, releasing any value previously protected in that slot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is out of bounds.
+    fn protect_slot<const N: usize>(
+        &mut self,
+        slot: usize,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        order: Ordering,
+    ) -> Protected<T, Self::Reclaim, N>;
+
+    /// Loads and protects the value currently stored in `atomic` in the given
+    /// `slot`, if it equals the `expected` value.
+    ///
+    /// # Errors
+    ///
+    /// Fails, if the loaded value is not equal to `expected`, in which case
+    /// the slot's previous protection is not guaranteed to be retained.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot` is out of bounds.
+    fn protect_slot_if_equal<const N: usize>(
+        &mut self,
+        slot: usize,
+        atomic: &Atomic<T, Self::Reclaim, N>,
+        expected: MarkedPtr<T, N>,
+        order: Ordering,
+    ) -> Result<Protected<T, Self::Reclaim, N>, NotEqual>;
+
+    /// Swaps the protections of slots `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` are out of bounds.
+    fn swap_slots(&mut self, a: usize, b: usize);
+
+    /// Rotates all slots by one, so that slot `i + 1` becomes slot `i` and
+    /// the first slot becomes the last slot.
+    ///
+    /// This moves the protection of the first slot to the last slot, where it
+    /// is usually replaced next.
+    fn shift(&mut self);
+
+    /// Releases the protection of the value protected in the given `slot`, if
+    /// any.
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// # Panics
+    ///
+    /// *May* panic if `slot` is out of bounds.
+    #[inline]
+    fn release_slot(&mut self, slot: usize) {
+        let _ = slot;
+    }
+
+    /// Releases the protections of all slots.
+    #[inline]
+    fn release_all(&mut self) {
+        for slot in 0..Self::SLOTS {
+            self.release_slot(slot);
+        }
+    }
 }